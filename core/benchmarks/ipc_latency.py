@@ -0,0 +1,97 @@
+"""Compare round-trip latency of the stdio and WebSocket transports.
+
+Spawns a core the same way the desktop app does (``--announce-port
+--stdio``) and sends N ``agent.list`` requests one at a time over each
+transport, waiting for every reply before sending the next.
+
+    python benchmarks/ipc_latency.py [-n 10000]
+"""
+
+from __future__ import annotations
+
+import argparse
+import asyncio
+import statistics
+import subprocess
+import sys
+import tempfile
+import time
+
+import websockets
+
+from mist_core.protocol import MSG_AGENT_LIST, Message, decode_message, encode_message
+
+
+def _request() -> Message:
+    return Message.create(MSG_AGENT_LIST, sender="bench", to="broker")
+
+
+def bench_stdio(proc: subprocess.Popen[str], n: int) -> list[float]:
+    samples = []
+    for _ in range(n):
+        msg = _request()
+        start = time.perf_counter()
+        proc.stdin.write(encode_message(msg) + "\n")
+        proc.stdin.flush()
+        reply = decode_message(proc.stdout.readline())
+        samples.append(time.perf_counter() - start)
+        assert reply.reply_to == msg.id
+    return samples
+
+
+async def bench_websocket(port: int, n: int) -> list[float]:
+    samples = []
+    async with websockets.connect(f"ws://127.0.0.1:{port}") as ws:
+        for _ in range(n):
+            msg = _request()
+            start = time.perf_counter()
+            await ws.send(encode_message(msg))
+            reply = decode_message(await ws.recv())
+            samples.append(time.perf_counter() - start)
+            assert reply.reply_to == msg.id
+    return samples
+
+
+def report(name: str, samples: list[float]) -> None:
+    us = sorted(s * 1e6 for s in samples)
+    p99 = us[int(len(us) * 0.99) - 1]
+    print(
+        f"{name:<10} n={len(us)}  mean={statistics.fmean(us):8.1f}us  "
+        f"p50={statistics.median(us):8.1f}us  p99={p99:8.1f}us  "
+        f"total={sum(samples):6.2f}s"
+    )
+
+
+def main() -> None:
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("-n", type=int, default=10_000, help="round trips per transport")
+    args = parser.parse_args()
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        proc = subprocess.Popen(
+            [
+                sys.executable, "-u", "-m", "mist_core.main",
+                "--data-dir", data_dir,
+                "--ws-port", "0", "--announce-port", "--stdio",
+            ],
+            stdin=subprocess.PIPE,
+            stdout=subprocess.PIPE,
+            stderr=subprocess.DEVNULL,
+            text=True,
+        )
+        try:
+            line = proc.stdout.readline()
+            if not line.startswith("LISTENING:"):
+                sys.exit(f"unexpected first line from core: {line!r}")
+            port = int(line.removeprefix("LISTENING:"))
+
+            report("stdio", bench_stdio(proc, args.n))
+            report("websocket", asyncio.run(bench_websocket(port, args.n)))
+        finally:
+            proc.stdin.close()
+            proc.terminate()
+            proc.wait()
+
+
+if __name__ == "__main__":
+    main()
@@ -1 +1,3 @@
 """mist-core: MIST core process — services, broker, admin agent, transport."""
+
+__version__ = "2.0.0"
@@ -15,7 +15,7 @@ from .broker.services import ServiceDispatcher
 from .llm.client import OllamaClient
 from .llm.queue import LLMQueue
 from .storage.settings import Settings
-from .transport import Server, WebSocketServer
+from .transport import Server, StdioServer, WebSocketServer
 
 log = logging.getLogger(__name__)
 
@@ -28,8 +28,11 @@ class Core:
         paths: Paths | None = None,
         ws_host: str = "127.0.0.1",
         ws_port: int = 8765,
+        announce_port: bool = False,
+        stdio: bool = False,
     ) -> None:
         self.paths = paths or Paths()
+        self._announce_port = announce_port
         self.db = Database(self.paths.db)
         self.settings = Settings(self.paths)
         self.llm_client = OllamaClient(self.settings)
@@ -50,6 +53,7 @@ class Core:
         self._ws_server = WebSocketServer(
             self.router.handle, host=ws_host, port=ws_port,
         )
+        self._stdio_server = StdioServer(self.router.handle) if stdio else None
 
     async def run(self) -> None:
         """Initialize and start all subsystems."""
@@ -61,15 +65,22 @@ class Core:
 
         await self._unix_server.start()
         await self._ws_server.start()
+        if self._announce_port:
+            # Read by the Tauri backend to find the port when started on 0.
+            print(f"LISTENING:{self._ws_server.port}", flush=True)
 
         queue_task = asyncio.create_task(self.llm_queue.run())
         log.info("core started")
 
+        servers = [
+            self._unix_server.serve_forever(),
+            self._ws_server.serve_forever(),
+        ]
+        if self._stdio_server is not None:
+            servers.append(self._stdio_server.serve_forever())
+
         try:
-            await asyncio.gather(
-                self._unix_server.serve_forever(),
-                self._ws_server.serve_forever(),
-            )
+            await asyncio.gather(*servers)
         except asyncio.CancelledError:
             log.info("core shutting down")
         finally:
@@ -102,6 +113,14 @@ def main() -> None:
         "--ws-port", type=int, default=8765,
         help="WebSocket port (default: 8765)",
     )
+    parser.add_argument(
+        "--announce-port", action="store_true",
+        help="Print LISTENING:<port> to stdout once the WebSocket is up",
+    )
+    parser.add_argument(
+        "--stdio", action="store_true",
+        help="Also accept messages as JSON lines on stdin, replying on stdout",
+    )
     parser.add_argument(
         "--verbose", "-v", action="store_true",
         help="Enable debug logging",
@@ -114,7 +133,10 @@ def main() -> None:
     )
 
     paths = Paths(root=args.data_dir)
-    core = Core(paths=paths, ws_host=args.ws_host, ws_port=args.ws_port)
+    core = Core(
+        paths=paths, ws_host=args.ws_host, ws_port=args.ws_port,
+        announce_port=args.announce_port, stdio=args.stdio,
+    )
     asyncio.run(core.run())
 
 
@@ -1,12 +1,14 @@
-"""Async Unix-socket and WebSocket transport for MIST messages."""
+"""Async Unix-socket, WebSocket, and stdio transport for MIST messages."""
 
 from __future__ import annotations
 
 import asyncio
 import json
 import logging
+import sys
+import threading
 from pathlib import Path
-from typing import Any, Awaitable, Callable
+from typing import Any, Awaitable, Callable, TextIO
 
 import websockets
 from websockets.asyncio.server import Server as WsServer, ServerConnection
@@ -239,7 +241,14 @@ class WebSocketServer:
             self._host,
             self._port,
         )
-        log.info("WebSocket listening on ws://%s:%d", self._host, self._port)
+        log.info("WebSocket listening on ws://%s:%d", self._host, self.port)
+
+    @property
+    def port(self) -> int:
+        """Bound port — differs from the requested one when started on port 0."""
+        if self._server is None:
+            return self._port
+        return self._server.sockets[0].getsockname()[1]
 
     async def stop(self) -> None:
         if self._server is not None:
@@ -277,3 +286,72 @@ class WebSocketServer:
                     log.exception("WebSocket handler error for %s", msg.type)
         except websockets.exceptions.ConnectionClosed:
             pass
+
+
+# ── Stdio Transport ─────────────────────────────────────────────────
+
+
+class StdioConnection:
+    """Sends messages as JSON lines on a text stream (normally stdout)."""
+
+    def __init__(self, stream: TextIO) -> None:
+        self._stream = stream
+
+    async def send(self, msg: Message) -> None:
+        self._stream.write(encode_message(msg) + "\n")
+        self._stream.flush()
+
+
+class StdioServer:
+    """Newline-delimited messages over stdin/stdout.
+
+    Used when the core is spawned by the desktop app, which exchanges
+    messages over the child's pipes instead of a WebSocket. Stdin is
+    read on a daemon thread: asyncio cannot wait on a console pipe on
+    every platform, and a blocked reader must not hold up shutdown.
+    """
+
+    def __init__(
+        self,
+        handler: Handler,
+        stdin: TextIO | None = None,
+        stdout: TextIO | None = None,
+    ) -> None:
+        self._handler = handler
+        self._stdin = stdin if stdin is not None else sys.stdin
+        self._conn = StdioConnection(stdout if stdout is not None else sys.stdout)
+
+    async def serve_forever(self) -> None:
+        """Handle messages until stdin is closed."""
+        loop = asyncio.get_running_loop()
+        lines: asyncio.Queue[str] = asyncio.Queue()
+
+        def pump() -> None:
+            for line in self._stdin:
+                loop.call_soon_threadsafe(lines.put_nowait, line)
+            loop.call_soon_threadsafe(lines.put_nowait, "")
+
+        threading.Thread(target=pump, name="stdio-reader", daemon=True).start()
+
+        while line := await lines.get():
+            line = line.rstrip("\n")
+            if not line:
+                continue
+            try:
+                msg = decode_message(line)
+            except ProtocolError as exc:
+                log.warning("malformed stdio message: %s", exc)
+                err = Message.create(
+                    type=MSG_ERROR,
+                    sender="server",
+                    to="unknown",
+                    payload={"error": str(exc)},
+                )
+                await self._conn.send(err)
+                continue
+
+            try:
+                await self._handler(msg, self._conn)
+            except Exception:
+                log.exception("stdio handler error for %s", msg.type)
+        log.info("stdin closed")
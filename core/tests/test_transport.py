@@ -1,6 +1,7 @@
 """Tests for mist_core.transport — Unix socket and WebSocket."""
 
 import asyncio
+import io
 import tempfile
 from pathlib import Path
 
@@ -15,7 +16,9 @@ from mist_core.protocol import (
     decode_message,
     encode_message,
 )
-from mist_core.transport import Client, Connection, Server, WebSocketServer, WebSocketConnection
+from mist_core.transport import (
+    Client, Connection, Server, StdioServer, WebSocketServer, WebSocketConnection,
+)
 
 
 async def _echo_handler(msg: Message, conn) -> None:
@@ -149,6 +152,17 @@ async def test_malformed_message_error_frame(sock_path):
 # ── WebSocket tests ─────────────────────────────────────────────────
 
 
+async def test_websocket_port_reports_bound_port():
+    ws_server = WebSocketServer(_echo_handler, host="127.0.0.1", port=0)
+    await ws_server.start()
+    try:
+        assert ws_server.port != 0
+        async with websockets.connect(f"ws://127.0.0.1:{ws_server.port}"):
+            pass
+    finally:
+        await ws_server.stop()
+
+
 async def test_websocket_echo_round_trip():
     ws_server = WebSocketServer(_echo_handler, host="127.0.0.1", port=0)
     # Start on a random port to avoid conflicts
@@ -177,3 +191,33 @@ async def test_websocket_echo_round_trip():
             assert reply.type == MSG_RESPONSE
             assert reply.payload == {"x": 42}
             assert reply.reply_to == msg.id
+
+
+# ── Stdio tests ─────────────────────────────────────────────────────
+
+
+async def test_stdio_echo_until_eof():
+    msgs = [
+        Message.create(MSG_COMMAND, sender="ui", to="echo-server", payload={"n": i})
+        for i in range(3)
+    ]
+    stdin = io.StringIO("".join(encode_message(m) + "\n" for m in msgs))
+    stdout = io.StringIO()
+
+    server = StdioServer(_echo_handler, stdin=stdin, stdout=stdout)
+    await asyncio.wait_for(server.serve_forever(), timeout=2.0)
+
+    replies = [decode_message(line) for line in stdout.getvalue().splitlines()]
+    assert [r.reply_to for r in replies] == [m.id for m in msgs]
+    assert [r.payload for r in replies] == [{"n": i} for i in range(3)]
+
+
+async def test_stdio_malformed_message_error_frame():
+    stdin = io.StringIO("not json\n")
+    stdout = io.StringIO()
+
+    server = StdioServer(_echo_handler, stdin=stdin, stdout=stdout)
+    await asyncio.wait_for(server.serve_forever(), timeout=2.0)
+
+    (reply,) = [decode_message(line) for line in stdout.getvalue().splitlines()]
+    assert reply.type == MSG_ERROR
@@ -1,3 +1,151 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+// Every app command must be listed here: declaring the manifest turns on
+// ACL checks for all of them, and each needs an `allow-*` permission in
+// one of the files under capabilities/.
+const COMMANDS: &[&str] = &[
+    "start_core",
+    "restart_core",
+    "get_core_status",
+    "get_core_port",
+    "read_config",
+    "write_config",
+    "pick_directory",
+    "pick_directory_async",
+    "open_log_file",
+    "get_log_path",
+    "check_port_available",
+    "get_system_info",
+    "write_temp_file",
+    "cleanup_temp_files",
+    "check_for_update",
+    "install_update",
+    "cancel_update",
+    "copy_to_clipboard",
+    "read_clipboard",
+    "request_notification_permission",
+    "send_native_notification",
+    "get_window_bounds",
+    "set_window_bounds",
+    "prevent_sleep",
+    "allow_sleep",
+    "hash_file",
+    "cancel_hash",
+    "tail_file",
+    "watch_file",
+    "unwatch_file",
+    "run_python_snippet",
+    "send_to_core",
+    "load_plugin",
+    "unload_plugin",
+    "list_plugins",
+    "get_env_vars",
+    "set_env_override",
+    "clear_env_override",
+    "reset_env_overrides",
+    "window_screenshot",
+    "save_screenshot",
+    "validate_python_environment",
+    "add_recent_file",
+    "get_recent_files",
+    "clear_recent_files",
+    "download_file",
+    "cancel_download",
+    "get_available_monitors",
+    "move_window_to_monitor",
+    "set_global_shortcut",
+    "remove_global_shortcut",
+    "list_global_shortcuts",
+    "compute_file_diff",
+    "diff_strings",
+    "compress_directory",
+    "extract_archive",
+    "set_window_title",
+    "set_title_template",
+    "resolve_title",
+    "execute_sql",
+    "window_always_on_top",
+    "export_diagnostics_bundle",
+    "schedule_task",
+    "cancel_task",
+    "list_tasks",
+    "http_get",
+    "http_post",
+    "parse_csv",
+    "verify_bundle_integrity",
+    "get_bundle_manifest",
+    "set_zoom_level",
+    "get_zoom_level",
+    "open_devtools",
+    "close_devtools",
+    "is_devtools_open",
+    "read_ini_config",
+    "write_ini_config",
+    "convert_ini_to_toml",
+    "set_rendering_hints",
+    "get_sandbox_status",
+    "keyboard_shortcut_cheatsheet",
+];
+
+/// Bundled data files. `bundle-manifest.json` in here lists every other
+/// file with its SHA-256, for `verify_bundle_integrity`.
+const RESOURCES_DIR: &str = "resources";
+const BUNDLE_MANIFEST: &str = "bundle-manifest.json";
+
 fn main() {
-    tauri_build::build()
+    write_bundle_manifest().expect("failed to write the bundle manifest");
+    tauri_build::try_build(
+        tauri_build::Attributes::new()
+            .app_manifest(tauri_build::AppManifest::new().commands(COMMANDS)),
+    )
+    .expect("failed to run tauri-build");
+}
+
+fn write_bundle_manifest() -> io::Result<()> {
+    println!("cargo:rerun-if-changed={RESOURCES_DIR}");
+    let dir = Path::new(RESOURCES_DIR);
+    let manifest = dir.join(BUNDLE_MANIFEST);
+    fs::create_dir_all(dir)?;
+
+    let mut files = Vec::new();
+    walk(dir, &mut files)?;
+    files.retain(|path| *path != manifest);
+    files.sort();
+    let mut entries = Vec::new();
+    for path in files {
+        let digest = Sha256::digest(fs::read(&path)?);
+        let relative = path.strip_prefix(dir).unwrap_or(&path);
+        entries.push(serde_json::json!({
+            "path": relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+            "sha256": digest.iter().map(|b| format!("{b:02x}")).collect::<String>(),
+        }));
+    }
+
+    let json = serde_json::to_string_pretty(&entries).map_err(io::Error::other)? + "\n";
+    // Rewriting an unchanged manifest would make cargo rerun this script
+    // on every build.
+    if fs::read_to_string(&manifest).ok().as_deref() != Some(json.as_str()) {
+        fs::write(&manifest, json)?;
+    }
+    Ok(())
+}
+
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            walk(&entry.path(), files)?;
+        } else {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
 }
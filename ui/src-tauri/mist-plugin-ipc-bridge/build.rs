@@ -0,0 +1,5 @@
+const COMMANDS: &[&str] = &["emit"];
+
+fn main() {
+    tauri_plugin::Builder::new(COMMANDS).build();
+}
@@ -0,0 +1,219 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::net::TcpStream;
+use tokio::sync::{watch, Notify};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use crate::PluginConfig;
+
+const MIN_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Handle to the running bridge.
+pub struct Bridge<R: Runtime> {
+    app: AppHandle<R>,
+    event_prefix: String,
+    outbox: Arc<Outbox>,
+    ws_url: watch::Sender<String>,
+}
+
+impl<R: Runtime> Bridge<R> {
+    pub(crate) fn start(app: AppHandle<R>, config: PluginConfig) -> Self {
+        let outbox = Arc::new(Outbox::new(config.buffer_size));
+        let (ws_url, url) = watch::channel(config.ws_url);
+        tauri::async_runtime::spawn(run(app.clone(), outbox.clone(), url));
+        Self {
+            app,
+            event_prefix: config.event_prefix,
+            outbox,
+            ws_url,
+        }
+    }
+
+    /// Point the bridge at a new core URL, reconnecting if it changed.
+    /// An empty URL disconnects.
+    pub fn set_ws_url(&self, url: impl Into<String>) {
+        let url = url.into();
+        self.ws_url.send_if_modified(|current| {
+            let changed = *current != url;
+            *current = url;
+            changed
+        });
+    }
+
+    /// Emit `event` to the app and, if it has the bridge prefix, queue it
+    /// for the core.
+    pub fn emit(&self, event: &str, payload: Value) -> tauri::Result<()> {
+        self.app.emit(event, &payload)?;
+        if event.starts_with(&self.event_prefix) {
+            self.outbox
+                .push(json!({ "tauri_event": event, "payload": payload }).to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Messages waiting for the core, oldest first.
+struct Outbox {
+    queue: Mutex<VecDeque<String>>,
+    capacity: usize,
+    ready: Notify,
+}
+
+impl Outbox {
+    fn new(capacity: usize) -> Self {
+        Self {
+            queue: Mutex::new(VecDeque::new()),
+            capacity: capacity.max(1),
+            ready: Notify::new(),
+        }
+    }
+
+    fn push(&self, message: String) {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        if queue.len() == self.capacity {
+            tracing::warn!("ipc bridge buffer full, dropping the oldest message");
+            queue.pop_front();
+        }
+        queue.push_back(message);
+        drop(queue);
+        self.ready.notify_one();
+    }
+
+    fn pop(&self) -> Option<String> {
+        self.queue
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop_front()
+    }
+
+    /// Put back a message that could not be sent. If newer messages have
+    /// filled the buffer since, it is the oldest, so it is dropped.
+    fn unpop(&self, message: String) {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        if queue.len() < self.capacity {
+            queue.push_front(message);
+        }
+    }
+}
+
+/// Keep a connection to the current URL open until the bridge is dropped.
+async fn run<R: Runtime>(app: AppHandle<R>, outbox: Arc<Outbox>, mut url: watch::Receiver<String>) {
+    let mut backoff = MIN_BACKOFF;
+    loop {
+        let target = url.borrow_and_update().clone();
+        if !target.is_empty() {
+            match tokio_tungstenite::connect_async(target.as_str()).await {
+                Ok((socket, _)) => {
+                    tracing::info!(url = %target, "ipc bridge connected");
+                    backoff = MIN_BACKOFF;
+                    session(&app, &outbox, socket, &mut url).await;
+                    tracing::info!(url = %target, "ipc bridge disconnected");
+                }
+                Err(e) => tracing::debug!(url = %target, "ipc bridge cannot connect: {e}"),
+            }
+        }
+        // Retry after a pause, or at once if the URL changes.
+        tokio::select! {
+            _ = tokio::time::sleep(backoff), if !target.is_empty() => {}
+            changed = url.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+            }
+        }
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// Pump messages both ways until the socket closes or the URL changes.
+async fn session<R: Runtime>(
+    app: &AppHandle<R>,
+    outbox: &Outbox,
+    socket: Socket,
+    url: &mut watch::Receiver<String>,
+) {
+    let (mut sink, mut stream) = socket.split();
+    // Flush whatever was buffered while disconnected.
+    outbox.ready.notify_one();
+    loop {
+        tokio::select! {
+            message = stream.next() => match message {
+                Some(Ok(Message::Text(text))) => deliver(app, text.as_str()),
+                Some(Ok(Message::Close(_))) | None => return,
+                Some(Ok(_)) => {}
+                Some(Err(e)) => {
+                    tracing::debug!("ipc bridge read failed: {e}");
+                    return;
+                }
+            },
+            () = outbox.ready.notified() => {
+                while let Some(message) = outbox.pop() {
+                    if let Err(e) = sink.send(Message::text(message.clone())).await {
+                        tracing::debug!("ipc bridge write failed: {e}");
+                        outbox.unpop(message);
+                        return;
+                    }
+                }
+            }
+            _ = url.changed() => return,
+        }
+    }
+}
+
+fn deliver<R: Runtime>(app: &AppHandle<R>, text: &str) {
+    let Some((event, message)) = inbound_event(text) else {
+        return;
+    };
+    if let Err(e) = app.emit(&event, message) {
+        tracing::warn!(%event, "ipc bridge cannot emit core message: {e}");
+    }
+}
+
+/// The event a core message asks to be emitted as, and its payload.
+fn inbound_event(text: &str) -> Option<(String, Value)> {
+    let message: Value = serde_json::from_str(text).ok()?;
+    let event = message.get("tauri_event")?.as_str()?.to_string();
+    Some((event, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outbox_drops_oldest_when_full() {
+        let outbox = Outbox::new(2);
+        for message in ["a", "b", "c"] {
+            outbox.push(message.into());
+        }
+        assert_eq!(outbox.pop().as_deref(), Some("b"));
+
+        outbox.unpop("b".into());
+        outbox.push("d".into());
+        outbox.unpop("lost".into());
+        assert_eq!(outbox.pop().as_deref(), Some("c"));
+        assert_eq!(outbox.pop().as_deref(), Some("d"));
+        assert_eq!(outbox.pop(), None);
+    }
+
+    #[test]
+    fn routes_messages_with_tauri_event() {
+        let (event, payload) =
+            inbound_event(r#"{"tauri_event": "agent:reply", "text": "hi"}"#).unwrap();
+        assert_eq!(event, "agent:reply");
+        assert_eq!(payload["text"], "hi");
+
+        assert!(inbound_event(r#"{"type": "text"}"#).is_none());
+        assert!(inbound_event(r#"{"tauri_event": 3}"#).is_none());
+        assert!(inbound_event("not json").is_none());
+    }
+}
@@ -0,0 +1,15 @@
+use serde_json::Value;
+use tauri::{AppHandle, Runtime};
+
+use crate::IpcBridgeExt;
+
+/// Emit `event`, forwarding it to the core if it has the bridge prefix.
+#[tauri::command]
+pub(crate) fn emit<R: Runtime>(
+    app: AppHandle<R>,
+    event: String,
+    payload: Option<Value>,
+) -> tauri::Result<()> {
+    app.ipc_bridge()
+        .emit(&event, payload.unwrap_or(Value::Null))
+}
@@ -0,0 +1,70 @@
+//! Tauri plugin that bridges Tauri events and the MIST core's WebSocket,
+//! so the frontend has a single channel for both.
+//!
+//! Core to frontend: every WebSocket text message that is a JSON object
+//! with a string `tauri_event` field is emitted as that Tauri event, with
+//! the whole message as payload.
+//!
+//! Frontend to core: Tauri has no wildcard listeners, so bridged events
+//! are emitted through the plugin, either with its `emit` command
+//! (`plugin:mist-plugin-ipc-bridge|emit`) or with [`Bridge::emit`]. The event is
+//! delivered locally as usual and, if its name starts with
+//! [`PluginConfig::event_prefix`], also sent to the core as
+//! `{"tauri_event": <name>, "payload": <payload>}`.
+//!
+//! A dropped connection is retried with exponential backoff. Outbound
+//! messages wait in a buffer of [`PluginConfig::buffer_size`] entries in
+//! the meantime; once it is full the oldest are dropped.
+
+use tauri::plugin::{Builder, TauriPlugin};
+use tauri::{Manager, Runtime};
+
+mod bridge;
+mod commands;
+
+pub use bridge::Bridge;
+
+/// Bridge settings, passed to [`init`].
+#[derive(Debug, Clone)]
+pub struct PluginConfig {
+    /// WebSocket URL of the core. Leave empty if it is not known yet and
+    /// call [`Bridge::set_ws_url`] once it is.
+    pub ws_url: String,
+    /// Events whose name starts with this are forwarded to the core.
+    pub event_prefix: String,
+    /// Outbound messages kept while disconnected.
+    pub buffer_size: usize,
+}
+
+impl Default for PluginConfig {
+    fn default() -> Self {
+        Self {
+            ws_url: String::new(),
+            event_prefix: "core-bridge:".into(),
+            buffer_size: 1000,
+        }
+    }
+}
+
+/// Access to the [`Bridge`] from any Tauri manager.
+pub trait IpcBridgeExt<R: Runtime> {
+    fn ipc_bridge(&self) -> &Bridge<R>;
+}
+
+impl<R: Runtime, T: Manager<R>> IpcBridgeExt<R> for T {
+    fn ipc_bridge(&self) -> &Bridge<R> {
+        self.state::<Bridge<R>>().inner()
+    }
+}
+
+/// Build the plugin. The connection is opened in the background once the
+/// app is set up.
+pub fn init<R: Runtime>(config: PluginConfig) -> TauriPlugin<R> {
+    Builder::new("mist-plugin-ipc-bridge")
+        .invoke_handler(tauri::generate_handler![commands::emit])
+        .setup(move |app, _api| {
+            app.manage(Bridge::start(app.clone(), config));
+            Ok(())
+        })
+        .build()
+}
@@ -0,0 +1,289 @@
+// Zip export and import of result directories.
+//
+// Both directions run on the blocking pool and report per-file progress
+// on `archive://progress`. Symlinks are never followed when compressing
+// and are skipped when extracting. Entries whose path would land outside
+// the destination are rejected before anything is written.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::error::MistError;
+
+#[derive(Clone, Serialize)]
+struct Progress {
+    operation: &'static str,
+    files_done: u32,
+    files_total: u32,
+}
+
+/// Zip the contents of `src` into `dest_zip` and return the size of the
+/// archive in bytes.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub async fn compress_directory<R: Runtime>(
+    app: AppHandle<R>,
+    src: String,
+    dest_zip: String,
+) -> Result<u64, MistError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        compress(
+            Path::new(&src),
+            Path::new(&dest_zip),
+            |files_done, files_total| emit(&app, "compress", files_done, files_total),
+        )
+    })
+    .await?
+}
+
+/// Unpack `src_zip` into `dest_dir` and return the number of files written.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub async fn extract_archive<R: Runtime>(
+    app: AppHandle<R>,
+    src_zip: String,
+    dest_dir: String,
+) -> Result<u32, MistError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        extract(
+            Path::new(&src_zip),
+            Path::new(&dest_dir),
+            |files_done, files_total| emit(&app, "extract", files_done, files_total),
+        )
+    })
+    .await?
+}
+
+fn emit<R: Runtime>(
+    app: &AppHandle<R>,
+    operation: &'static str,
+    files_done: u32,
+    files_total: u32,
+) {
+    let _ = app.emit(
+        "archive://progress",
+        Progress {
+            operation,
+            files_done,
+            files_total,
+        },
+    );
+}
+
+fn compress(src: &Path, dest: &Path, mut progress: impl FnMut(u32, u32)) -> Result<u64, MistError> {
+    let src = src
+        .canonicalize()
+        .map_err(|e| MistError::Io(format!("{}: {e}", src.display())))?;
+    if !src.is_dir() {
+        return Err(MistError::Validation(format!(
+            "{} is not a directory",
+            src.display()
+        )));
+    }
+    let dest_dir = match dest.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let dest_dir = dest_dir
+        .canonicalize()
+        .map_err(|e| MistError::Io(format!("{}: {e}", dest_dir.display())))?;
+    if dest_dir.starts_with(&src) {
+        return Err(MistError::Validation(
+            "the archive must not be written inside the directory being compressed".into(),
+        ));
+    }
+
+    let mut entries = Vec::new();
+    walk(&src, &mut entries)
+        .map_err(|e| MistError::Io(format!("failed to read {}: {e}", src.display())))?;
+    let files_total = entries.iter().filter(|(_, is_dir)| !is_dir).count() as u32;
+
+    let file = File::create(dest)
+        .map_err(|e| MistError::Io(format!("failed to create {}: {e}", dest.display())))?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut files_done = 0;
+    for (path, is_dir) in entries {
+        let name = entry_name(path.strip_prefix(&src).unwrap_or(&path));
+        if is_dir {
+            zip.add_directory(name, options)
+                .map_err(|e| MistError::Io(e.to_string()))?;
+            continue;
+        }
+        zip.start_file(name, options)
+            .map_err(|e| MistError::Io(e.to_string()))?;
+        File::open(&path)
+            .and_then(|mut file| io::copy(&mut file, &mut zip))
+            .map_err(|e| MistError::Io(format!("failed to add {}: {e}", path.display())))?;
+        files_done += 1;
+        progress(files_done, files_total);
+    }
+
+    let written = zip
+        .finish()
+        .map_err(|e| MistError::Io(e.to_string()))?
+        .into_inner()
+        .map_err(|e| e.into_error())?;
+    Ok(written.metadata()?.len())
+}
+
+/// Collect everything below `dir` as `(path, is_dir)`, in sorted order.
+/// Symlinks are skipped rather than followed.
+fn walk(dir: &Path, entries: &mut Vec<(PathBuf, bool)>) -> io::Result<()> {
+    let mut children: Vec<_> = fs::read_dir(dir)?.collect::<io::Result<_>>()?;
+    children.sort_by_key(|entry| entry.file_name());
+    for child in children {
+        let file_type = child.file_type()?;
+        if file_type.is_dir() {
+            entries.push((child.path(), true));
+            walk(&child.path(), entries)?;
+        } else if file_type.is_file() {
+            entries.push((child.path(), false));
+        }
+    }
+    Ok(())
+}
+
+/// Zip entry names always use `/`, whatever the platform separator.
+fn entry_name(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn extract(src: &Path, dest: &Path, mut progress: impl FnMut(u32, u32)) -> Result<u32, MistError> {
+    let file = File::open(src)
+        .map_err(|e| MistError::Io(format!("failed to open {}: {e}", src.display())))?;
+    let mut archive =
+        ZipArchive::new(file).map_err(|e| MistError::Io(format!("{}: {e}", src.display())))?;
+
+    // Check every entry before writing any, so a malicious archive leaves
+    // nothing behind.
+    let mut files_total = 0;
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index(i)
+            .map_err(|e| MistError::Io(e.to_string()))?;
+        if entry.enclosed_name().is_none() {
+            return Err(MistError::Validation(format!(
+                "archive entry {:?} escapes the destination directory",
+                String::from_utf8_lossy(entry.name_raw())
+            )));
+        }
+        if !entry.is_dir() && !entry.is_symlink() {
+            files_total += 1;
+        }
+    }
+
+    fs::create_dir_all(dest)
+        .map_err(|e| MistError::Io(format!("failed to create {}: {e}", dest.display())))?;
+    let mut files_done = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| MistError::Io(e.to_string()))?;
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        let out = dest.join(&relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&out)
+                .map_err(|e| MistError::Io(format!("failed to create {}: {e}", out.display())))?;
+            continue;
+        }
+        if entry.is_symlink() {
+            tracing::warn!(path = %relative.display(), "skipping symlink in archive");
+            continue;
+        }
+        if let Some(parent) = out.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                MistError::Io(format!("failed to create {}: {e}", parent.display()))
+            })?;
+        }
+        File::create(&out)
+            .and_then(|mut file| io::copy(&mut entry, &mut file))
+            .map_err(|e| MistError::Io(format!("failed to write {}: {e}", out.display())))?;
+        files_done += 1;
+        progress(files_done, files_total);
+    }
+    Ok(files_done)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write;
+
+    #[test]
+    fn round_trips_directory() {
+        let scratch = tempfile::tempdir().unwrap();
+        let root = scratch.path();
+        let src = root.join("results");
+        fs::create_dir_all(src.join("plots/empty")).unwrap();
+        fs::write(src.join("summary.csv"), "a,b\n1,2\n").unwrap();
+        fs::write(src.join("plots/fit.svg"), "<svg/>").unwrap();
+
+        let zip_path = root.join("results.zip");
+        let mut seen = Vec::new();
+        let size = compress(&src, &zip_path, |done, total| seen.push((done, total))).unwrap();
+        assert_eq!(size, fs::metadata(&zip_path).unwrap().len());
+        assert_eq!(seen, [(1, 2), (2, 2)]);
+
+        let out = root.join("out");
+        assert_eq!(extract(&zip_path, &out, |_, _| {}).unwrap(), 2);
+        assert_eq!(
+            fs::read_to_string(out.join("summary.csv")).unwrap(),
+            "a,b\n1,2\n"
+        );
+        assert_eq!(
+            fs::read_to_string(out.join("plots/fit.svg")).unwrap(),
+            "<svg/>"
+        );
+        assert!(out.join("plots/empty").is_dir());
+    }
+
+    #[test]
+    fn refuses_archive_inside_source() {
+        let scratch = tempfile::tempdir().unwrap();
+        let src = scratch.path();
+        let err = compress(src, &src.join("self.zip"), |_, _| {}).unwrap_err();
+        assert!(
+            matches!(&err, MistError::Validation(m) if m.contains("inside")),
+            "{err}"
+        );
+        assert!(!src.join("self.zip").exists());
+    }
+
+    #[test]
+    fn rejects_path_traversal() {
+        let scratch = tempfile::tempdir().unwrap();
+        let root = scratch.path();
+        let zip_path = root.join("evil.zip");
+        let mut zip = ZipWriter::new(File::create(&zip_path).unwrap());
+        zip.start_file("ok.txt", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"fine").unwrap();
+        zip.start_file("../escaped.txt", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"gotcha").unwrap();
+        zip.finish().unwrap();
+
+        let out = root.join("out");
+        let err = extract(&zip_path, &out, |_, _| {}).unwrap_err();
+        assert!(
+            matches!(&err, MistError::Validation(m) if m.contains("escapes")),
+            "{err}"
+        );
+        assert!(!root.join("escaped.txt").exists());
+        assert!(!out.join("ok.txt").exists());
+    }
+}
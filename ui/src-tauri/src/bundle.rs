@@ -0,0 +1,146 @@
+// Install integrity checks, for telling a corrupted installation apart
+// from a real bug.
+//
+// build.rs writes `resources/bundle-manifest.json`, listing each file
+// under `resources/` with its SHA-256, and the manifest is bundled along
+// with those files. Verification re-hashes every listed file and reports
+// the ones that are missing or have changed.
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::path::BaseDirectory;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::{error::MistError, hash};
+
+const MANIFEST_RESOURCE: &str = "resources/bundle-manifest.json";
+
+/// A bundled file and its SHA-256 at build time. `path` is relative to
+/// `resources/` and uses `/` separators.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct BundleIntegrityReport {
+    pub ok: bool,
+    pub failures: Vec<String>,
+}
+
+/// Check every bundled resource against the manifest. `failures` lists
+/// the paths that are missing, unreadable or no longer match.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub async fn verify_bundle_integrity<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<BundleIntegrityReport, MistError> {
+    let manifest = manifest_path(&app)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let entries = read_manifest(&manifest)?;
+        let root = manifest.parent().unwrap_or(Path::new("."));
+        Ok(verify(root, &entries))
+    })
+    .await?
+}
+
+/// The bundled manifest, for display.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub fn get_bundle_manifest<R: Runtime>(app: AppHandle<R>) -> Result<Vec<ManifestEntry>, MistError> {
+    read_manifest(&manifest_path(&app)?)
+}
+
+fn manifest_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, MistError> {
+    Ok(app
+        .path()
+        .resolve(MANIFEST_RESOURCE, BaseDirectory::Resource)?)
+}
+
+fn read_manifest(path: &Path) -> Result<Vec<ManifestEntry>, MistError> {
+    let raw = fs::read_to_string(path)
+        .map_err(|e| MistError::Io(format!("failed to read {}: {e}", path.display())))?;
+    serde_json::from_str(&raw)
+        .map_err(|e| MistError::Config(format!("invalid {}: {e}", path.display())))
+}
+
+fn verify(root: &Path, entries: &[ManifestEntry]) -> BundleIntegrityReport {
+    let failures: Vec<String> = entries
+        .iter()
+        .filter(|entry| {
+            let digest = File::open(root.join(&entry.path)).and_then(hash::sha256);
+            match digest {
+                Ok(digest) => !digest.eq_ignore_ascii_case(&entry.sha256),
+                Err(e) => {
+                    tracing::warn!(path = %entry.path, "cannot check bundled file: {e}");
+                    true
+                }
+            }
+        })
+        .map(|entry| entry.path.clone())
+        .collect();
+    BundleIntegrityReport {
+        ok: failures.is_empty(),
+        failures,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, sha256: &str) -> ManifestEntry {
+        ManifestEntry {
+            path: path.into(),
+            sha256: sha256.into(),
+        }
+    }
+
+    // SHA-256 of "abc".
+    const ABC: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    #[test]
+    fn reports_missing_and_changed_files() {
+        let scratch = tempfile::tempdir().unwrap();
+        let root = scratch.path();
+        fs::create_dir_all(root.join("data")).unwrap();
+        fs::write(root.join("data/intact.txt"), "abc").unwrap();
+        fs::write(root.join("changed.txt"), "abd").unwrap();
+
+        let report = verify(
+            root,
+            &[
+                entry("data/intact.txt", ABC),
+                entry("changed.txt", ABC),
+                entry("missing.txt", ABC),
+            ],
+        );
+        assert_eq!(
+            report,
+            BundleIntegrityReport {
+                ok: false,
+                failures: vec!["changed.txt".into(), "missing.txt".into()],
+            }
+        );
+        assert!(verify(root, &[entry("data/intact.txt", ABC)]).ok);
+    }
+
+    #[test]
+    fn parses_manifest() {
+        let scratch = tempfile::tempdir().unwrap();
+        let root = scratch.path();
+        let path = root.join("bundle-manifest.json");
+        fs::write(
+            &path,
+            format!(r#"[{{"path": "a.txt", "sha256": "{ABC}"}}]"#),
+        )
+        .unwrap();
+        assert_eq!(read_manifest(&path).unwrap(), [entry("a.txt", ABC)]);
+
+        fs::write(&path, "{}").unwrap();
+        assert!(matches!(read_manifest(&path), Err(MistError::Config(_))));
+    }
+}
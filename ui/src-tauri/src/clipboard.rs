@@ -0,0 +1,84 @@
+// Native clipboard access, for webviews where `navigator.clipboard` is
+// blocked.
+//
+// Both commands are gated by the `clipboard` capability
+// (capabilities/clipboard.json), which grants them to the main window's
+// bundled frontend only; remote origins cannot invoke them. The plugin's
+// own JS commands are not granted at all, so these two are the only way
+// in. The capability cannot tell a user action from a WebSocket message,
+// so the frontend must only call `read_clipboard` from a user gesture and
+// never on behalf of data received from the core.
+
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::error::MistError;
+
+/// Put `text` on the system clipboard.
+#[tauri::command]
+#[tracing::instrument(skip(app, text))]
+pub async fn copy_to_clipboard<R: Runtime>(
+    app: AppHandle<R>,
+    text: String,
+) -> Result<(), MistError> {
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| MistError::Io(format!("clipboard unavailable: {e}")))
+}
+
+/// Return the text currently on the system clipboard.
+///
+/// Async so it stays off the main thread, where reading can deadlock on
+/// Linux while the webview owns the selection.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub async fn read_clipboard<R: Runtime>(app: AppHandle<R>) -> Result<String, MistError> {
+    app.clipboard()
+        .read_text()
+        .map_err(|e| MistError::Io(format!("clipboard unavailable: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tauri::test::{get_ipc_response, mock_builder, mock_context, noop_assets};
+    use tauri::WebviewWindowBuilder;
+
+    use crate::test_util::request;
+
+    #[test]
+    fn round_trips_text_or_reports_unavailable() {
+        let app = mock_builder()
+            .plugin(tauri_plugin_clipboard_manager::init())
+            .invoke_handler(tauri::generate_handler![copy_to_clipboard, read_clipboard])
+            .build(mock_context(noop_assets()))
+            .unwrap();
+        let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+            .build()
+            .unwrap();
+
+        let copied = get_ipc_response(
+            &webview,
+            request("copy_to_clipboard", serde_json::json!({ "text": "mist" })),
+        );
+        match copied {
+            Ok(_) => {
+                let read =
+                    get_ipc_response(&webview, request("read_clipboard", serde_json::json!({})))
+                        .unwrap()
+                        .deserialize::<String>()
+                        .unwrap();
+                assert_eq!(read, "mist");
+            }
+            // Headless CI has no clipboard; the error must say so.
+            Err(e) => assert!(
+                e["message"]
+                    .as_str()
+                    .unwrap()
+                    .starts_with("clipboard unavailable"),
+                "unexpected error: {e}"
+            ),
+        }
+    }
+}
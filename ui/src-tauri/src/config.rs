@@ -0,0 +1,108 @@
+// Desktop settings, persisted as `config.toml` in the app data directory.
+//
+// The frontend reads and writes the raw TOML; the backend only checks
+// that it parses before saving. All access goes through `ConfigLock` so
+// overlapping saves cannot interleave with reads.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Runtime, State};
+
+use crate::error::MistError;
+
+const CONFIG_FILE: &str = "config.toml";
+
+const DEFAULT_CONFIG: &str = r#"# MIST desktop settings
+
+[window]
+# Hide to the tray instead of quitting when the main window is closed.
+minimize_to_tray = false
+
+[updater]
+# Update manifest URLs; falls back to the ones in tauri.conf.json if empty.
+endpoints = []
+
+[http_allowlist]
+# URL prefixes that http_get and http_post may fetch.
+prefixes = []
+
+[sandbox]
+# Run the core as `nobody` (Linux and macOS, when MIST runs as root).
+run_as_restricted_user = false
+# Paths the core may access, passed to it as MIST_ALLOWED_PATHS.
+allowed_paths = []
+"#;
+
+/// Serialises access to the config file.
+#[derive(Default)]
+pub struct ConfigLock(Mutex<()>);
+
+/// Return the config file contents, creating it with defaults if absent.
+#[tauri::command]
+#[tracing::instrument(skip(app, lock))]
+pub fn read_config<R: Runtime>(
+    app: AppHandle<R>,
+    lock: State<'_, ConfigLock>,
+) -> Result<String, MistError> {
+    let _guard = lock.0.lock()?;
+    read_or_create(&app)
+}
+
+/// Replace the config file with `toml_content` if it is valid TOML.
+#[tauri::command]
+#[tracing::instrument(skip(app, lock, toml_content))]
+pub fn write_config<R: Runtime>(
+    app: AppHandle<R>,
+    lock: State<'_, ConfigLock>,
+    toml_content: String,
+) -> Result<(), MistError> {
+    toml::from_str::<toml::Table>(&toml_content)?;
+    let _guard = lock.0.lock()?;
+    let path = config_path(&app)?;
+    fs::write(&path, toml_content)
+        .map_err(|e| MistError::Io(format!("failed to write {}: {e}", path.display())))
+}
+
+/// Apply `edit` to the config file in place. Comments and layout written
+/// by the frontend are preserved.
+pub fn update<R: Runtime>(
+    app: &AppHandle<R>,
+    edit: impl FnOnce(&mut toml_edit::DocumentMut),
+) -> Result<(), MistError> {
+    let lock = app.state::<ConfigLock>();
+    let _guard = lock.0.lock()?;
+    let mut doc: toml_edit::DocumentMut = read_or_create(app)?
+        .parse()
+        .map_err(|e| MistError::Config(format!("invalid config: {e}")))?;
+    edit(&mut doc);
+    let path = config_path(app)?;
+    fs::write(&path, doc.to_string())
+        .map_err(|e| MistError::Io(format!("failed to write {}: {e}", path.display())))
+}
+
+/// Parsed config, for use by other backend modules.
+pub fn load<R: Runtime>(app: &AppHandle<R>) -> Result<toml::Table, MistError> {
+    let lock = app.state::<ConfigLock>();
+    let _guard = lock.0.lock()?;
+    let raw = read_or_create(app)?;
+    Ok(toml::from_str(&raw)?)
+}
+
+fn read_or_create<R: Runtime>(app: &AppHandle<R>) -> Result<String, MistError> {
+    let path = config_path(app)?;
+    if !path.exists() {
+        fs::write(&path, DEFAULT_CONFIG)
+            .map_err(|e| MistError::Io(format!("failed to create {}: {e}", path.display())))?;
+    }
+    fs::read_to_string(&path)
+        .map_err(|e| MistError::Io(format!("failed to read {}: {e}", path.display())))
+}
+
+fn config_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, MistError> {
+    let dir = app.path().app_data_dir()?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| MistError::Io(format!("failed to create {}: {e}", dir.display())))?;
+    Ok(dir.join(CONFIG_FILE))
+}
@@ -0,0 +1,486 @@
+// Lifecycle management for the Python core process.
+//
+// The core is spawned as a child of the Tauri backend. Its stdout and
+// stderr are forwarded line-by-line to the frontend as `core://log`
+// events, and the child is shut down when the main window goes away.
+// `restart_core` reports progress on `core://status` and repeated
+// crashes on `core://error`.
+//
+// The core is started on port 0 and announces the port it bound as the
+// first line of stdout (`LISTENING:<port>`), which is re-emitted as
+// `core://port`. The ipc bridge plugin is pointed at it at the same time.
+//
+// The core also runs with `--stdio`: `send_to_core` writes JSON lines to
+// its stdin, and every later stdout line that parses as JSON comes back
+// as a `core://message` event. This skips the WebSocket for
+// high-frequency traffic. The pipes belong to the child, so a restart
+// replaces them along with the process.
+//
+// The `[sandbox]` config table can have the core run as an unprivileged
+// user; see `sandbox`.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use mist_plugin_ipc_bridge::Bridge;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::{env, error::MistError, sandbox};
+
+/// How long to wait for the core to exit after asking it to terminate.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+/// Failures closer together than this count as consecutive.
+const FAILURE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Upper bound on the extra delay added between failing restarts.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// How long the core has to announce its WebSocket port after spawning.
+const PORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Handle to the managed core process, if one is running.
+#[derive(Default)]
+pub struct CoreState {
+    child: Mutex<Option<Child>>,
+    /// Data directory from the last successful `start_core`, reused on restart.
+    data_dir: Mutex<Option<String>>,
+    failures: Mutex<Failures>,
+    started_at: Mutex<Option<Instant>>,
+    restarts: AtomicU32,
+    /// WebSocket port announced by the running core.
+    port: Mutex<Option<u16>>,
+    /// Write end of the running core's stdin, used by `send_to_core`.
+    stdin: Mutex<Option<ChildStdin>>,
+    /// Held for the whole of a start or restart, so only one runs at a time.
+    lifecycle: tokio::sync::Mutex<()>,
+}
+
+#[derive(Default)]
+struct Failures {
+    count: u32,
+    last: Option<Instant>,
+}
+
+impl Failures {
+    /// Record a failure and return the consecutive failure count.
+    fn record(&mut self, now: Instant) -> u32 {
+        match self.last {
+            Some(last) if now.duration_since(last) < FAILURE_WINDOW => self.count += 1,
+            _ => self.count = 1,
+        }
+        self.last = Some(now);
+        self.count
+    }
+
+    /// Extra delay before the next spawn: 1s, 2s, 4s, ... capped at `MAX_BACKOFF`.
+    fn backoff(&self) -> Duration {
+        if self.count < 2 {
+            return Duration::ZERO;
+        }
+        let secs = 1u64 << (self.count - 2).min(5);
+        Duration::from_secs(secs).min(MAX_BACKOFF)
+    }
+}
+
+/// Snapshot of the managed core returned by `get_core_status`.
+#[derive(Serialize)]
+pub struct CoreStatus {
+    pub pid: Option<u32>,
+    pub running: bool,
+    pub uptime_secs: u64,
+    pub restart_count: u32,
+}
+
+#[derive(Clone, Serialize)]
+struct LogLine {
+    stream: &'static str,
+    line: String,
+}
+
+#[derive(Clone, Serialize)]
+struct PortEvent {
+    port: u16,
+}
+
+#[derive(Clone, Serialize)]
+struct ErrorEvent {
+    message: String,
+    failures: u32,
+}
+
+/// Spawn the Python core with `config_path` as its data directory.
+///
+/// Returns the PID of the new process.
+#[tauri::command]
+#[tracing::instrument(skip(app, state))]
+pub fn start_core<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, CoreState>,
+    config_path: String,
+) -> Result<u32, MistError> {
+    let _lifecycle = state
+        .lifecycle
+        .try_lock()
+        .map_err(|_| MistError::Process("core is restarting".into()))?;
+    let mut guard = state.child.lock()?;
+    if let Some(child) = guard.as_mut() {
+        if child.try_wait()?.is_none() {
+            return Err(MistError::Process(format!(
+                "core already running (pid {})",
+                child.id()
+            )));
+        }
+    }
+
+    let child = spawn(&app, &config_path)?;
+    let pid = child.id();
+    tracing::info!(pid, "core started");
+    *guard = Some(child);
+    *state.data_dir.lock()? = Some(config_path);
+    *state.started_at.lock()? = Some(Instant::now());
+    Ok(pid)
+}
+
+/// Stop the running core (if any), wait `delay_ms`, and spawn it again
+/// with the arguments from the original `start_core` call.
+///
+/// If the core keeps dying, consecutive failures within `FAILURE_WINDOW`
+/// add an exponential backoff on top of `delay_ms`. Overlapping restarts
+/// run one after the other, and `start_core` is refused while one is in
+/// progress.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub async fn restart_core<R: Runtime>(app: AppHandle<R>, delay_ms: u64) -> Result<(), MistError> {
+    let state = app.state::<CoreState>();
+    // The child slot is empty while we wait below; without this a second
+    // restart would spawn a core of its own alongside ours.
+    let _lifecycle = state.lifecycle.lock().await;
+    let data_dir = state
+        .data_dir
+        .lock()?
+        .clone()
+        .ok_or_else(|| MistError::Process("core has not been started".into()))?;
+
+    let _ = app.emit("core://status", "stopping");
+    state.stdin.lock()?.take();
+    let previous = state.child.lock()?.take();
+    let crashed = match previous {
+        Some(mut child) => {
+            tauri::async_runtime::spawn_blocking(move || {
+                let crashed = matches!(child.try_wait(), Ok(Some(status)) if !status.success());
+                terminate(&mut child);
+                crashed
+            })
+            .await?
+        }
+        None => false,
+    };
+    if crashed {
+        record_failure(&app, "core exited unexpectedly")?;
+    }
+
+    let backoff = state.failures.lock()?.backoff();
+    tokio::time::sleep(Duration::from_millis(delay_ms) + backoff).await;
+
+    let _ = app.emit("core://status", "starting");
+    let child = match spawn(&app, &data_dir) {
+        Ok(child) => child,
+        Err(e) => {
+            record_failure(&app, &e.to_string())?;
+            return Err(e);
+        }
+    };
+    let stray = state.child.lock()?.replace(child);
+    if let Some(mut stray) = stray {
+        tracing::warn!(pid = stray.id(), "stopping core spawned during restart");
+        tauri::async_runtime::spawn_blocking(move || terminate(&mut stray));
+    }
+    *state.started_at.lock()? = Some(Instant::now());
+    state.restarts.fetch_add(1, Ordering::Relaxed);
+    let _ = app.emit("core://status", "ready");
+    Ok(())
+}
+
+/// Report whether the managed core is alive, and for how long.
+#[tauri::command]
+#[tracing::instrument(skip(state))]
+pub fn get_core_status(state: State<'_, CoreState>) -> CoreStatus {
+    let restart_count = state.restarts.load(Ordering::Relaxed);
+    let mut guard = state.child.lock().unwrap_or_else(|e| e.into_inner());
+    let (pid, running) = match guard.as_mut() {
+        Some(child) => (Some(child.id()), matches!(child.try_wait(), Ok(None))),
+        None => (None, false),
+    };
+    let uptime_secs = match *state.started_at.lock().unwrap_or_else(|e| e.into_inner()) {
+        Some(started) if running => started.elapsed().as_secs(),
+        _ => 0,
+    };
+    CoreStatus {
+        pid,
+        running,
+        uptime_secs,
+        restart_count,
+    }
+}
+
+/// Stop the managed core, if any. Called when the app is shutting down.
+pub fn shutdown<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<CoreState>();
+    if let Ok(mut stdin) = state.stdin.lock() {
+        stdin.take();
+    }
+    let Ok(mut guard) = state.child.lock() else {
+        return;
+    };
+    if let Some(mut child) = guard.take() {
+        tracing::info!(pid = child.id(), "stopping core");
+        terminate(&mut child);
+    }
+}
+
+/// Restart the core from a menu item or shortcut, where nobody awaits the
+/// result. A failure is logged and reported on `core://error`.
+pub fn spawn_restart<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = restart_core(app.clone(), 0).await {
+            tracing::warn!("could not restart core: {e}");
+            emit_error(&app, e.to_string());
+        }
+    });
+}
+
+/// WebSocket port of the running core, for frontends that missed `core://port`.
+#[tauri::command]
+#[tracing::instrument(skip(state))]
+pub fn get_core_port(state: State<'_, CoreState>) -> Option<u16> {
+    *state.port.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Send one message to the core over its stdin. `msg` must be JSON; it is
+/// re-serialised onto a single line before writing.
+#[tauri::command]
+#[tracing::instrument(skip(state, msg))]
+pub fn send_to_core(state: State<'_, CoreState>, msg: String) -> Result<(), MistError> {
+    let value: serde_json::Value = serde_json::from_str(&msg)
+        .map_err(|e| MistError::Validation(format!("invalid message: {e}")))?;
+    write_message(&state, &value)
+}
+
+/// Send `msg` to the core over stdin, for backend modules that need to
+/// notify it directly.
+pub fn send_message<R: Runtime>(
+    app: &AppHandle<R>,
+    msg: &serde_json::Value,
+) -> Result<(), MistError> {
+    write_message(&app.state::<CoreState>(), msg)
+}
+
+fn write_message(state: &CoreState, msg: &serde_json::Value) -> Result<(), MistError> {
+    let mut line = msg.to_string();
+    line.push('\n');
+
+    let mut guard = state.stdin.lock()?;
+    let stdin = guard
+        .as_mut()
+        .ok_or_else(|| MistError::Process("core is not running".into()))?;
+    stdin
+        .write_all(line.as_bytes())
+        .and_then(|()| stdin.flush())
+        .map_err(|e| MistError::Io(format!("failed to write to core: {e}")))
+}
+
+fn spawn<R: Runtime>(app: &AppHandle<R>, data_dir: &str) -> Result<Child, MistError> {
+    let python = python_path(app);
+    let state = app.state::<CoreState>();
+    *state.port.lock()? = None;
+    let mut command = Command::new(&python);
+    command
+        .args(["-u", "-m", "mist_core.main", "--data-dir", data_dir])
+        .args(["--ws-port", "0", "--announce-port", "--stdio"])
+        .envs(env::overrides(app))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let restricted = sandbox::prepare(app, &mut command);
+    let mut child = command
+        .spawn()
+        .map_err(|e| MistError::Process(format!("failed to spawn {}: {e}", python.display())))?;
+    sandbox::started(app, restricted, &child);
+
+    *state.stdin.lock()? = child.stdin.take();
+    if let Some(stdout) = child.stdout.take() {
+        forward_stdout(app.clone(), stdout);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_lines(app.clone(), "stderr", stderr);
+    }
+    Ok(child)
+}
+
+fn record_failure<R: Runtime>(app: &AppHandle<R>, message: &str) -> Result<(), MistError> {
+    let state = app.state::<CoreState>();
+    let failures = state.failures.lock()?.record(Instant::now());
+    tracing::warn!(failures, "{message}");
+    let _ = app.emit(
+        "core://error",
+        ErrorEvent {
+            message: message.to_string(),
+            failures,
+        },
+    );
+    Ok(())
+}
+
+/// Report `message` on `core://error` without counting it as a crash.
+fn emit_error<R: Runtime>(app: &AppHandle<R>, message: String) {
+    let failures = app
+        .state::<CoreState>()
+        .failures
+        .lock()
+        .map(|f| f.count)
+        .unwrap_or_default();
+    let _ = app.emit("core://error", ErrorEvent { message, failures });
+}
+
+/// Resolve the Python interpreter used to run the core.
+///
+/// `MIST_PYTHON` wins if set; otherwise the interpreter bundled in the
+/// resource directory is used, falling back to whatever is on `PATH`.
+pub fn python_path<R: Runtime>(app: &AppHandle<R>) -> PathBuf {
+    if let Some(path) = std::env::var_os("MIST_PYTHON") {
+        return PathBuf::from(path);
+    }
+    let bundled = if cfg!(windows) {
+        "python/python.exe"
+    } else {
+        "python/bin/python3"
+    };
+    if let Ok(dir) = app.path().resource_dir() {
+        let candidate = dir.join(bundled);
+        if candidate.exists() {
+            return candidate;
+        }
+    }
+    PathBuf::from(if cfg!(windows) { "python" } else { "python3" })
+}
+
+/// Read the core's stdout. The first line is expected to be the port
+/// announcement, and `core://error` is emitted if it does not show up in
+/// time or the pipe closes first. After that, JSON lines are IPC replies
+/// for `core://message`; anything else is treated as log output.
+fn forward_stdout<R: Runtime>(app: AppHandle<R>, pipe: impl Read + Send + 'static) {
+    let (tx, rx) = mpsc::channel::<()>();
+
+    let watchdog = app.clone();
+    thread::spawn(move || {
+        let message = match rx.recv_timeout(PORT_TIMEOUT) {
+            Ok(()) => return,
+            Err(RecvTimeoutError::Timeout) => "timeout waiting for port announcement",
+            Err(RecvTimeoutError::Disconnected) => "core exited before announcing its port",
+        };
+        tracing::warn!("{message}");
+        emit_error(&watchdog, message.into());
+    });
+
+    thread::spawn(move || {
+        let mut lines = BufReader::new(pipe).lines().map_while(Result::ok);
+        match lines.next() {
+            Some(line) => match parse_port(&line) {
+                Some(port) => {
+                    tracing::info!(port, "core listening");
+                    if let Ok(mut slot) = app.state::<CoreState>().port.lock() {
+                        *slot = Some(port);
+                    }
+                    if let Some(bridge) = app.try_state::<Bridge<R>>() {
+                        bridge.set_ws_url(format!("ws://127.0.0.1:{port}"));
+                    }
+                    let _ = tx.send(());
+                    let _ = app.emit("core://port", PortEvent { port });
+                }
+                None => {
+                    let _ = app.emit(
+                        "core://log",
+                        LogLine {
+                            stream: "stdout",
+                            line,
+                        },
+                    );
+                }
+            },
+            None => return,
+        }
+        // Keep `tx` alive until the pipe closes so a bad first line is
+        // reported as a failure rather than silently dropped.
+        for line in lines {
+            match serde_json::from_str::<serde_json::Value>(&line) {
+                Ok(msg) => {
+                    let _ = app.emit("core://message", msg);
+                }
+                Err(_) => {
+                    let _ = app.emit(
+                        "core://log",
+                        LogLine {
+                            stream: "stdout",
+                            line,
+                        },
+                    );
+                }
+            }
+        }
+        drop(tx);
+    });
+}
+
+fn parse_port(line: &str) -> Option<u16> {
+    line.trim().strip_prefix("LISTENING:")?.parse().ok()
+}
+
+fn forward_lines<R: Runtime>(
+    app: AppHandle<R>,
+    stream: &'static str,
+    pipe: impl Read + Send + 'static,
+) {
+    thread::spawn(move || {
+        for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+            let _ = app.emit("core://log", LogLine { stream, line });
+        }
+    });
+}
+
+/// Ask the child to exit, then kill it if it has not done so within
+/// `SHUTDOWN_GRACE`.
+fn terminate(child: &mut Child) {
+    if let Ok(Some(_)) = child.try_wait() {
+        return;
+    }
+
+    #[cfg(unix)]
+    unsafe {
+        libc::kill(child.id() as libc::pid_t, libc::SIGTERM);
+    }
+    // There is no graceful equivalent on Windows; `kill` maps to
+    // `TerminateProcess`.
+    #[cfg(windows)]
+    let _ = child.kill();
+
+    let deadline = Instant::now() + SHUTDOWN_GRACE;
+    while Instant::now() < deadline {
+        match child.try_wait() {
+            Ok(Some(_)) => return,
+            Ok(None) => thread::sleep(Duration::from_millis(100)),
+            Err(_) => break,
+        }
+    }
+    tracing::warn!(pid = child.id(), "core ignored SIGTERM, killing");
+    let _ = child.kill();
+    let _ = child.wait();
+}
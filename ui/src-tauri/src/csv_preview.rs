@@ -0,0 +1,190 @@
+// CSV previews for the file picker, so users can check column names and
+// the first rows before handing a file to the core.
+//
+// The delimiter is guessed from the first 10 lines: `,`, tab and `;` are
+// tried in that order and the one giving the most rows with the same
+// number of columns wins, earlier candidates winning ties. Fields that
+// are not valid UTF-8 are decoded lossily.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+
+use csv::{ByteRecord, ReaderBuilder};
+use serde::Serialize;
+
+use crate::error::MistError;
+
+const DEFAULT_MAX_ROWS: usize = 100;
+const SAMPLE_LINES: usize = 10;
+const DELIMITERS: [u8; 3] = [b',', b'\t', b';'];
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CsvPreview {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub total_rows: usize,
+    pub truncated: bool,
+}
+
+/// Column names and up to `max_rows` (default 100) data rows of the CSV
+/// at `path`. Without `has_header` the columns are named `col_0`,
+/// `col_1`, and so on.
+#[tauri::command]
+#[tracing::instrument]
+pub async fn parse_csv(
+    path: String,
+    max_rows: Option<usize>,
+    has_header: bool,
+) -> Result<CsvPreview, MistError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let open =
+            || File::open(&path).map_err(|e| MistError::Io(format!("failed to open {path}: {e}")));
+        let sample = read_lines(open()?, SAMPLE_LINES)
+            .map_err(|e| MistError::Io(format!("failed to read {path}: {e}")))?;
+        preview(
+            open()?,
+            detect_delimiter(&sample),
+            max_rows.unwrap_or(DEFAULT_MAX_ROWS),
+            has_header,
+        )
+        .map_err(|e| MistError::Io(format!("failed to read {path}: {e}")))
+    })
+    .await?
+}
+
+fn read_lines(reader: impl Read, count: usize) -> std::io::Result<Vec<u8>> {
+    let mut reader = BufReader::new(reader);
+    let mut sample = Vec::new();
+    for _ in 0..count {
+        if reader.read_until(b'\n', &mut sample)? == 0 {
+            break;
+        }
+    }
+    Ok(sample)
+}
+
+fn detect_delimiter(sample: &[u8]) -> u8 {
+    let mut best = (DELIMITERS[0], (false, 0, 0));
+    for delimiter in DELIMITERS {
+        let mut reader = ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(sample);
+        // A sample cut off inside a quoted field ends in an error; the
+        // records before it still count.
+        let counts: Vec<usize> = reader
+            .byte_records()
+            .map_while(Result::ok)
+            .map(|record| record.len())
+            .collect();
+        let Some(&columns) = counts
+            .iter()
+            .max_by_key(|&&n| (counts.iter().filter(|&&m| m == n).count(), n))
+        else {
+            continue;
+        };
+        let consistent = counts.iter().filter(|&&n| n == columns).count();
+        // A delimiter that never splits a row is no delimiter at all.
+        let score = (columns > 1, consistent, columns);
+        if score > best.1 {
+            best = (delimiter, score);
+        }
+    }
+    best.0
+}
+
+fn preview(
+    reader: impl Read,
+    delimiter: u8,
+    max_rows: usize,
+    has_header: bool,
+) -> Result<CsvPreview, csv::Error> {
+    let mut records = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(reader)
+        .into_byte_records();
+    let headers = match has_header {
+        true => records.next().transpose()?.map(|record| fields(&record)),
+        false => None,
+    };
+
+    let mut rows = Vec::new();
+    let mut total_rows = 0;
+    for record in records {
+        let record = record?;
+        total_rows += 1;
+        if rows.len() < max_rows {
+            rows.push(fields(&record));
+        }
+    }
+
+    let headers = headers.unwrap_or_else(|| {
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        (0..columns).map(|i| format!("col_{i}")).collect()
+    });
+    Ok(CsvPreview {
+        headers,
+        truncated: total_rows > rows.len(),
+        rows,
+        total_rows,
+    })
+}
+
+fn fields(record: &ByteRecord) -> Vec<String> {
+    record
+        .iter()
+        .map(|field| String::from_utf8_lossy(field).into_owned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(data: &[u8], max_rows: usize, has_header: bool) -> CsvPreview {
+        let sample = read_lines(data, SAMPLE_LINES).unwrap();
+        preview(data, detect_delimiter(&sample), max_rows, has_header).unwrap()
+    }
+
+    #[test]
+    fn reads_tab_separated() {
+        let data = b"sample\tvalue\tunit\nA1\t0.5\tmg, dry\nA2\t0.7\tmg, dry\n";
+        assert_eq!(detect_delimiter(data), b'\t');
+        let csv = parse(data, 100, true);
+        assert_eq!(csv.headers, ["sample", "value", "unit"]);
+        assert_eq!(csv.rows[1], ["A2", "0.7", "mg, dry"]);
+        assert_eq!((csv.total_rows, csv.truncated), (2, false));
+    }
+
+    #[test]
+    fn keeps_quoted_commas() {
+        let data = b"id,label\n1,\"Smith, J.\"\n2,\"a \"\"quoted\"\", b\"\n";
+        assert_eq!(detect_delimiter(data), b',');
+        let csv = parse(data, 100, true);
+        assert_eq!(csv.rows, [["1", "Smith, J."], ["2", "a \"quoted\", b"]]);
+    }
+
+    #[test]
+    fn detects_semicolons() {
+        let data = b"a;b;c\n1,5;2,5;3\n4;5;6\n";
+        assert_eq!(detect_delimiter(data), b';');
+    }
+
+    #[test]
+    fn names_columns_without_header() {
+        let data = b"1,2,3\n4,5,6\n7,8,9\n";
+        let csv = parse(data, 2, false);
+        assert_eq!(csv.headers, ["col_0", "col_1", "col_2"]);
+        assert_eq!(csv.rows, [["1", "2", "3"], ["4", "5", "6"]]);
+        assert_eq!((csv.total_rows, csv.truncated), (3, true));
+    }
+
+    #[test]
+    fn replaces_invalid_utf8() {
+        let csv = parse(b"name\ncaf\xe9\n", 100, true);
+        assert_eq!(csv.rows, [["caf\u{fffd}"]]);
+    }
+}
@@ -0,0 +1,60 @@
+// `mist://` URI scheme handling.
+//
+// `mist://open?token=<token>` brings the running window to the front and
+// emits `deeplink://open` with the token. A second launch carrying the
+// URL is folded into the first instance by the single-instance plugin,
+// which forwards it here.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::window;
+
+#[derive(Clone, Serialize)]
+struct OpenEvent {
+    token: String,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> tauri_plugin_deep_link::Result<()> {
+    // Installed bundles register the scheme at install time; this covers
+    // development builds on platforms that allow runtime registration.
+    #[cfg(any(windows, target_os = "linux"))]
+    app.deep_link().register_all()?;
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            handle_url(&handle, &url);
+        }
+    });
+
+    // URLs that launched the app arrive before the listener exists.
+    if let Some(urls) = app.deep_link().get_current()? {
+        for url in urls {
+            handle_url(app, &url);
+        }
+    }
+    Ok(())
+}
+
+fn handle_url<R: Runtime>(app: &AppHandle<R>, url: &Url) {
+    match parse_open(url) {
+        Some(token) => {
+            window::show_main(app);
+            let _ = app.emit("deeplink://open", OpenEvent { token });
+        }
+        None => tracing::warn!(%url, "ignoring unrecognised deep link"),
+    }
+}
+
+/// Extract the token from `mist://open?token=...`.
+fn parse_open(url: &Url) -> Option<String> {
+    if url.scheme() != "mist" || url.host_str() != Some("open") {
+        return None;
+    }
+    url.query_pairs()
+        .find(|(key, _)| key == "token")
+        .map(|(_, value)| value.into_owned())
+        .filter(|token| !token.is_empty())
+}
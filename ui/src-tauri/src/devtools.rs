@@ -0,0 +1,98 @@
+// DevTools control, so the frontend can open the inspector from a
+// shortcut instead of the context menu.
+//
+// Tauri only links the inspector into debug builds. Release builds keep
+// the commands so the frontend needs no special casing, but opening and
+// closing fail and `is_devtools_open` is always false.
+
+use tauri::{AppHandle, Runtime};
+#[cfg(debug_assertions)]
+use tauri::{Manager, WebviewWindow};
+
+use crate::error::MistError;
+#[cfg(debug_assertions)]
+use crate::window;
+
+#[cfg(not(debug_assertions))]
+const UNAVAILABLE: &str = "devtools not available in release builds";
+
+/// Open the DevTools of the main window.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub fn open_devtools<R: Runtime>(app: AppHandle<R>) -> Result<(), MistError> {
+    #[cfg(debug_assertions)]
+    {
+        main_webview(&app)?.open_devtools();
+        Ok(())
+    }
+    #[cfg(not(debug_assertions))]
+    {
+        let _ = app;
+        Err(MistError::Process(UNAVAILABLE.into()))
+    }
+}
+
+/// Close the DevTools of the main window.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub fn close_devtools<R: Runtime>(app: AppHandle<R>) -> Result<(), MistError> {
+    #[cfg(debug_assertions)]
+    {
+        main_webview(&app)?.close_devtools();
+        Ok(())
+    }
+    #[cfg(not(debug_assertions))]
+    {
+        let _ = app;
+        Err(MistError::Process(UNAVAILABLE.into()))
+    }
+}
+
+/// Whether the DevTools of the main window are open.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub fn is_devtools_open<R: Runtime>(app: AppHandle<R>) -> bool {
+    #[cfg(debug_assertions)]
+    {
+        main_webview(&app).is_ok_and(|webview| webview.is_devtools_open())
+    }
+    #[cfg(not(debug_assertions))]
+    {
+        let _ = app;
+        false
+    }
+}
+
+/// Open the DevTools of the main window, or close them if open.
+#[cfg(debug_assertions)]
+pub fn toggle<R: Runtime>(app: &AppHandle<R>) {
+    match main_webview(app) {
+        Ok(webview) if webview.is_devtools_open() => webview.close_devtools(),
+        Ok(webview) => webview.open_devtools(),
+        Err(e) => tracing::warn!("could not toggle devtools: {e}"),
+    }
+}
+
+#[cfg(debug_assertions)]
+fn main_webview<R: Runtime>(app: &AppHandle<R>) -> Result<WebviewWindow<R>, MistError> {
+    app.get_webview_window(window::MAIN)
+        .ok_or_else(|| MistError::Process("main window not found".into()))
+}
+
+// The debug variants need a real webview, so only the release ones are
+// tested (`cargo test --release`).
+#[cfg(all(test, not(debug_assertions)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_build_refuses_devtools() {
+        let app = tauri::test::mock_app();
+        let unavailable = Err(MistError::Process(
+            "devtools not available in release builds".into(),
+        ));
+        assert_eq!(open_devtools(app.handle().clone()), unavailable);
+        assert_eq!(close_devtools(app.handle().clone()), unavailable);
+        assert!(!is_devtools_open(app.handle().clone()));
+    }
+}
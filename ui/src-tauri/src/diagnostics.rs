@@ -0,0 +1,211 @@
+// Support bundles: one zip with everything a bug report needs.
+//
+// The bundle holds the rolling log files, the config with secrets
+// redacted and `system_info.json`. A part that cannot be collected is
+// listed in `errors.txt` instead of failing the export. The zip is
+// written next to its destination under a `.partial` name and renamed
+// into place once complete, so an interrupted export leaves nothing
+// half-written behind.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use tauri::{AppHandle, Runtime};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::{config, error::MistError, logs, system};
+
+/// Config values whose key contains any of these are redacted.
+const SECRET_MARKERS: [&str; 3] = ["password", "token", "key"];
+const REDACTED: &str = "[REDACTED]";
+
+/// Write a support bundle to `dest_path` and return its size in bytes.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub async fn export_diagnostics_bundle<R: Runtime>(
+    app: AppHandle<R>,
+    dest_path: String,
+) -> Result<u64, MistError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut entries = Vec::new();
+        let mut errors = Vec::new();
+
+        match sanitised_config(&app) {
+            Ok(config) => entries.push(("config.toml".to_string(), config.into_bytes())),
+            Err(e) => errors.push(format!("config.toml: {e}")),
+        }
+
+        let info = system::collect(app.package_info().version.to_string());
+        match serde_json::to_vec_pretty(&info) {
+            Ok(json) => entries.push(("system_info.json".to_string(), json)),
+            Err(e) => errors.push(format!("system_info.json: {e}")),
+        }
+
+        let Some(dir) = logs::log_dir(&app.config().identifier) else {
+            errors.push("logs: no log directory on this platform".into());
+            return write_bundle(Path::new(&dest_path), &entries, &errors);
+        };
+        match logs::log_files(&dir) {
+            Ok(files) => {
+                for path in files {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    match fs::read(&path) {
+                        Ok(bytes) => entries.push((format!("logs/{name}"), bytes)),
+                        Err(e) => errors.push(format!("{}: {e}", path.display())),
+                    }
+                }
+            }
+            Err(e) => errors.push(format!("{}: {e}", dir.display())),
+        }
+
+        write_bundle(Path::new(&dest_path), &entries, &errors)
+    })
+    .await?
+}
+
+fn sanitised_config<R: Runtime>(app: &AppHandle<R>) -> Result<String, MistError> {
+    let mut config = config::load(app)?;
+    redact(&mut config);
+    toml::to_string(&config).map_err(|e| MistError::Config(e.to_string()))
+}
+
+/// Replace every value whose key looks like a secret, at any depth.
+fn redact(table: &mut toml::Table) {
+    for (key, value) in table.iter_mut() {
+        let key = key.to_lowercase();
+        if SECRET_MARKERS.iter().any(|marker| key.contains(marker)) {
+            *value = toml::Value::String(REDACTED.into());
+        } else {
+            redact_value(value);
+        }
+    }
+}
+
+fn redact_value(value: &mut toml::Value) {
+    match value {
+        toml::Value::Table(table) => redact(table),
+        toml::Value::Array(items) => items.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+/// Zip `entries`, plus `errors.txt` if there are `errors`, into `dest`
+/// via a `.partial` sibling. Returns the size of the finished archive.
+fn write_bundle(
+    dest: &Path,
+    entries: &[(String, Vec<u8>)],
+    errors: &[String],
+) -> Result<u64, MistError> {
+    let name = dest
+        .file_name()
+        .ok_or_else(|| MistError::Validation(format!("{} is not a file path", dest.display())))?;
+    let mut partial = name.to_os_string();
+    partial.push(".partial");
+    let partial = dest.with_file_name(partial);
+
+    let written = write_zip(&partial, entries, errors).and_then(|()| {
+        fs::rename(&partial, dest)
+            .map_err(|e| MistError::Io(format!("failed to write {}: {e}", dest.display())))
+    });
+    if let Err(e) = written {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+    Ok(fs::metadata(dest)?.len())
+}
+
+fn write_zip(
+    path: &Path,
+    entries: &[(String, Vec<u8>)],
+    errors: &[String],
+) -> Result<(), MistError> {
+    let zip_error = |e: zip::result::ZipError| MistError::Io(e.to_string());
+    let file = File::create(path)
+        .map_err(|e| MistError::Io(format!("failed to create {}: {e}", path.display())))?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, bytes) in entries {
+        zip.start_file(name.as_str(), options).map_err(zip_error)?;
+        zip.write_all(bytes)?;
+    }
+    if !errors.is_empty() {
+        zip.start_file("errors.txt", options).map_err(zip_error)?;
+        for error in errors {
+            writeln!(zip, "{error}")?;
+        }
+    }
+    let file = zip
+        .finish()
+        .map_err(zip_error)?
+        .into_inner()
+        .map_err(|e| e.into_error())?;
+    Ok(file.sync_all()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Read;
+
+    use zip::ZipArchive;
+
+    #[test]
+    fn redacts_secret_keys_at_any_depth() {
+        let mut config: toml::Table = toml::from_str(
+            r#"
+            api_token = "abc"
+            [window]
+            minimize_to_tray = true
+            [[servers]]
+            name = "lab"
+            Password = "hunter2"
+            [updater]
+            signing_key = { path = "/keys/mist" }
+            "#,
+        )
+        .unwrap();
+        redact(&mut config);
+        assert_eq!(config["api_token"].as_str(), Some(REDACTED));
+        assert_eq!(config["window"]["minimize_to_tray"].as_bool(), Some(true));
+        assert_eq!(config["servers"][0]["name"].as_str(), Some("lab"));
+        assert_eq!(config["servers"][0]["Password"].as_str(), Some(REDACTED));
+        assert_eq!(config["updater"]["signing_key"].as_str(), Some(REDACTED));
+    }
+
+    #[test]
+    fn writes_bundle_with_errors_entry() {
+        let scratch = tempfile::tempdir().unwrap();
+        let dir = scratch.path();
+        let dest = dir.join("support.zip");
+        let entries = vec![("logs/mist.log".to_string(), b"{}\n".to_vec())];
+        let errors = vec!["config.toml: unreadable".to_string()];
+
+        let size = write_bundle(&dest, &entries, &errors).unwrap();
+        assert_eq!(size, fs::metadata(&dest).unwrap().len());
+        assert!(!dir.join("support.zip.partial").exists());
+
+        let mut zip = ZipArchive::new(File::open(&dest).unwrap()).unwrap();
+        let mut text = String::new();
+        zip.by_name("errors.txt")
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        assert_eq!(text, "config.toml: unreadable\n");
+        assert!(zip.by_name("logs/mist.log").is_ok());
+    }
+
+    #[test]
+    fn leaves_nothing_behind_on_failure() {
+        let scratch = tempfile::tempdir().unwrap();
+        let dir = scratch.path();
+        // The destination is an existing directory, so the rename fails.
+        let dest = dir.join("taken");
+        fs::create_dir_all(dest.join("child")).unwrap();
+
+        assert!(write_bundle(&dest, &[], &[]).is_err());
+        assert!(!dir.join("taken.partial").exists());
+    }
+}
@@ -0,0 +1,65 @@
+// Native file dialogs.
+
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_dialog::{DialogExt, FileDialogBuilder, FilePath};
+use tokio::sync::oneshot;
+
+use crate::{error::MistError, window};
+
+/// Show a folder picker modal to the main window and block until the user
+/// chooses a directory or cancels (`None`).
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub async fn pick_directory<R: Runtime>(
+    app: AppHandle<R>,
+    title: String,
+    default_path: Option<String>,
+) -> Result<Option<String>, MistError> {
+    let dialog = folder_dialog(&app, title, default_path)?;
+    // The blocking API must stay off the main thread.
+    let picked =
+        tauri::async_runtime::spawn_blocking(move || dialog.blocking_pick_folder()).await?;
+    picked.map(to_string).transpose()
+}
+
+/// Like `pick_directory`, but driven by the dialog's completion callback
+/// instead of a blocked thread.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub async fn pick_directory_async<R: Runtime>(
+    app: AppHandle<R>,
+    title: String,
+    default_path: Option<String>,
+) -> Result<Option<String>, MistError> {
+    let (tx, rx) = oneshot::channel();
+    folder_dialog(&app, title, default_path)?.pick_folder(move |picked| {
+        let _ = tx.send(picked);
+    });
+    let picked = rx
+        .await
+        .map_err(|_| MistError::Process("dialog closed without a result".into()))?;
+    picked.map(to_string).transpose()
+}
+
+fn folder_dialog<R: Runtime>(
+    app: &AppHandle<R>,
+    title: String,
+    default_path: Option<String>,
+) -> Result<FileDialogBuilder<R>, MistError> {
+    let start = match default_path.map(PathBuf::from) {
+        Some(path) if path.exists() => path,
+        _ => app.path().home_dir()?,
+    };
+    let mut dialog = app.dialog().file().set_title(title).set_directory(start);
+    if let Some(main) = app.get_webview_window(window::MAIN) {
+        dialog = dialog.set_parent(&main);
+    }
+    Ok(dialog)
+}
+
+fn to_string(path: FilePath) -> Result<String, MistError> {
+    let path = path.into_path().map_err(|e| MistError::Io(e.to_string()))?;
+    Ok(path.to_string_lossy().into_owned())
+}
@@ -0,0 +1,136 @@
+// Unified diffs of text, for comparing config files between runs.
+
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use similar::TextDiff;
+
+use crate::error::MistError;
+
+/// Files larger than this are refused rather than diffed.
+const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// How much of each file is scanned for NUL bytes to detect binaries.
+const SNIFF_LEN: u64 = 8 * 1024;
+
+const DEFAULT_CONTEXT: usize = 3;
+
+/// Unified diff from `path_a` to `path_b`, with `context_lines` (default
+/// 3) of unchanged lines around each change. Empty if the files match.
+#[tauri::command]
+#[tracing::instrument]
+pub async fn compute_file_diff(
+    path_a: String,
+    path_b: String,
+    context_lines: Option<usize>,
+) -> Result<String, MistError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let a = read_text(Path::new(&path_a))?;
+        let b = read_text(Path::new(&path_b))?;
+        Ok(unified(&a, &b, &path_a, &path_b, context_lines))
+    })
+    .await?
+}
+
+/// Unified diff of two in-memory strings.
+#[tauri::command]
+#[tracing::instrument(skip(a, b))]
+pub fn diff_strings(a: String, b: String, context_lines: Option<usize>) -> String {
+    unified(&a, &b, "a", "b", context_lines)
+}
+
+fn unified(a: &str, b: &str, name_a: &str, name_b: &str, context_lines: Option<usize>) -> String {
+    TextDiff::from_lines(a, b)
+        .unified_diff()
+        .context_radius(context_lines.unwrap_or(DEFAULT_CONTEXT))
+        .header(name_a, name_b)
+        .to_string()
+}
+
+fn read_text(path: &Path) -> Result<String, MistError> {
+    let size = fs::metadata(path)
+        .map_err(|e| MistError::Io(format!("failed to stat {}: {e}", path.display())))?
+        .len();
+    if size > MAX_FILE_SIZE {
+        return Err(MistError::Validation(format!(
+            "{} is too large to diff ({size} bytes, limit {MAX_FILE_SIZE})",
+            path.display()
+        )));
+    }
+
+    let mut bytes = Vec::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_end(&mut bytes))
+        .map_err(|e| MistError::Io(format!("failed to read {}: {e}", path.display())))?;
+    if is_binary(&bytes) {
+        return Err(MistError::Validation("binary file".into()));
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(SNIFF_LEN as usize).any(|&b| b == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::NamedTempFile;
+
+    /// A file holding `contents`, deleted when the handle drops.
+    fn scratch_file(contents: &[u8]) -> NamedTempFile {
+        let file = NamedTempFile::new().unwrap();
+        fs::write(file.path(), contents).unwrap();
+        file
+    }
+
+    #[test]
+    fn identical_inputs_give_empty_diff() {
+        let text = "a = 1\nb = 2\n".to_string();
+        assert_eq!(diff_strings(text.clone(), text, None), "");
+    }
+
+    #[test]
+    fn different_inputs_replace_every_line() {
+        let diff = diff_strings("one\ntwo\n".into(), "three\nfour\n".into(), None);
+        assert_eq!(
+            diff,
+            "--- a\n+++ b\n@@ -1,2 +1,2 @@\n-one\n-two\n+three\n+four\n"
+        );
+    }
+
+    #[test]
+    fn context_lines_are_configurable() {
+        let a: String = (0..20).map(|i| format!("{i}\n")).collect();
+        let b = a.replace("10\n", "ten\n");
+        let diff = diff_strings(a, b, Some(1));
+        assert!(
+            diff.contains("@@ -10,3 +10,3 @@\n 9\n-10\n+ten\n 11\n"),
+            "{diff}"
+        );
+    }
+
+    #[test]
+    fn rejects_binary_files() {
+        let file = scratch_file(b"PK\x03\x04\x00\x00");
+        assert_eq!(
+            read_text(file.path()).unwrap_err(),
+            MistError::Validation("binary file".into())
+        );
+    }
+
+    #[test]
+    fn diffs_files() {
+        let a = scratch_file(b"x\n");
+        let b = scratch_file(b"y\n");
+        let diff = tauri::async_runtime::block_on(compute_file_diff(
+            a.path().display().to_string(),
+            b.path().display().to_string(),
+            None,
+        ))
+        .unwrap();
+        assert!(diff.ends_with("@@ -1 +1 @@\n-x\n+y\n"), "{diff}");
+    }
+}
@@ -0,0 +1,165 @@
+// Large file downloads with progress and cancellation.
+//
+// The body is streamed to `<dest>.part` through a 256 KB buffer and
+// renamed into place once complete, so multi-gigabyte files never sit in
+// memory and a failed or cancelled download never clobbers an existing
+// file. Each download has a caller-chosen id, which tags its
+// `download://progress` events and is what `cancel_download` takes.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use reqwest::redirect::Policy;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime, State};
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio_util::sync::CancellationToken;
+
+use crate::error::MistError;
+
+const BUFFER_SIZE: usize = 256 * 1024;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const MAX_REDIRECTS: usize = 5;
+
+/// Cancellation tokens for downloads in flight, keyed by id.
+#[derive(Default)]
+pub struct Downloads(Mutex<HashMap<String, CancellationToken>>);
+
+#[derive(Clone, Serialize)]
+struct Progress {
+    id: String,
+    bytes_received: u64,
+    total_bytes: Option<u64>,
+}
+
+/// Download `url` to `dest_path`, reporting progress on
+/// `download://progress`. Returns the number of bytes written.
+#[tauri::command]
+#[tracing::instrument(skip(app, downloads))]
+pub async fn download_file<R: Runtime>(
+    app: AppHandle<R>,
+    downloads: State<'_, Downloads>,
+    id: String,
+    url: String,
+    dest_path: String,
+) -> Result<u64, MistError> {
+    let dest = PathBuf::from(&dest_path);
+    let part = PathBuf::from(format!("{dest_path}.part"));
+
+    let token = CancellationToken::new();
+    {
+        let mut active = downloads.0.lock()?;
+        if active.contains_key(&id) {
+            return Err(MistError::Validation(format!(
+                "download {id:?} is already running"
+            )));
+        }
+        active.insert(id.clone(), token.clone());
+    }
+
+    let result = tokio::select! {
+        result = fetch(&app, &id, &url, &part) => result,
+        _ = token.cancelled() => Err(MistError::Cancelled),
+    };
+    downloads
+        .0
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&id);
+
+    let result = match result {
+        Ok(written) => tokio::fs::rename(&part, &dest)
+            .await
+            .map(|()| written)
+            .map_err(|e| {
+                MistError::Io(format!(
+                    "failed to move download to {}: {e}",
+                    dest.display()
+                ))
+            }),
+        Err(e) => Err(e),
+    };
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&part).await;
+    }
+    result
+}
+
+/// Abort the download started with `id`.
+#[tauri::command]
+#[tracing::instrument(skip(downloads))]
+pub fn cancel_download(downloads: State<'_, Downloads>, id: String) -> Result<(), MistError> {
+    downloads
+        .0
+        .lock()?
+        .get(&id)
+        .map(CancellationToken::cancel)
+        .ok_or_else(|| MistError::Validation(format!("no download with id {id:?}")))
+}
+
+async fn fetch<R: Runtime>(
+    app: &AppHandle<R>,
+    id: &str,
+    url: &str,
+    part: &Path,
+) -> Result<u64, MistError> {
+    // Creating the file first checks that the destination is writable
+    // before any data is transferred.
+    let file = tokio::fs::File::create(part)
+        .await
+        .map_err(|e| MistError::Io(format!("failed to create {}: {e}", part.display())))?;
+    let mut writer = BufWriter::with_capacity(BUFFER_SIZE, file);
+
+    let client = client_builder()
+        .redirect(Policy::limited(MAX_REDIRECTS))
+        .build()
+        .map_err(|e| MistError::Io(e.to_string()))?;
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| MistError::Io(e.to_string()))?;
+    let total_bytes = response.content_length();
+
+    let mut bytes_received = 0u64;
+    let mut last_emit: Option<Instant> = None;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| MistError::Io(e.to_string()))?
+    {
+        writer
+            .write_all(&chunk)
+            .await
+            .map_err(|e| MistError::Io(format!("failed to write {}: {e}", part.display())))?;
+        bytes_received += chunk.len() as u64;
+        if last_emit.is_none_or(|at| at.elapsed() >= PROGRESS_INTERVAL) {
+            last_emit = Some(Instant::now());
+            let _ = app.emit(
+                "download://progress",
+                Progress {
+                    id: id.to_string(),
+                    bytes_received,
+                    total_bytes,
+                },
+            );
+        }
+    }
+    writer
+        .flush()
+        .await
+        .map_err(|e| MistError::Io(format!("failed to write {}: {e}", part.display())))?;
+    Ok(bytes_received)
+}
+
+pub fn client_builder() -> reqwest::ClientBuilder {
+    // reqwest is built without a bundled crypto provider; use ring, as the
+    // updater plugin does.
+    if rustls::crypto::CryptoProvider::get_default().is_none() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+    }
+    reqwest::Client::builder().user_agent(concat!("mist/", env!("CARGO_PKG_VERSION")))
+}
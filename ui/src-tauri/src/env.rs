@@ -0,0 +1,128 @@
+// Environment overrides for the Python core.
+//
+// Overrides are layered on top of the app's own environment when the core
+// is spawned, so a virtualenv or container setup can be configured without
+// touching the system environment. They are persisted to the `[env]`
+// config table and take effect on the next start or restart.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Runtime, State};
+
+use crate::{config, error::MistError};
+
+/// Never reported by `get_env_vars`, even when set.
+const HIDDEN_VARS: &[&str] = &[
+    "AWS_SECRET_ACCESS_KEY",
+    "AWS_SESSION_TOKEN",
+    "AZURE_CLIENT_SECRET",
+    "GITHUB_TOKEN",
+    "GH_TOKEN",
+    "NPM_TOKEN",
+    "OPENAI_API_KEY",
+    "ANTHROPIC_API_KEY",
+    "HF_TOKEN",
+];
+
+/// Variables set for the core on top of the inherited environment.
+#[derive(Default)]
+pub struct EnvOverrides(Mutex<HashMap<String, String>>);
+
+/// Load the overrides saved in the `[env]` config table.
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), MistError> {
+    let config = config::load(app)?;
+    let Some(section) = config.get("env").and_then(|env| env.as_table()) else {
+        return Ok(());
+    };
+    let state = app.state::<EnvOverrides>();
+    let mut overrides = state.0.lock()?;
+    for (key, value) in section {
+        match value.as_str() {
+            Some(value) => {
+                overrides.insert(key.clone(), value.to_string());
+            }
+            None => tracing::warn!(%key, "ignoring non-string [env] value"),
+        }
+    }
+    Ok(())
+}
+
+/// Current overrides, for spawning the core.
+pub fn overrides<R: Runtime>(app: &AppHandle<R>) -> HashMap<String, String> {
+    app.state::<EnvOverrides>()
+        .0
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// The environment the core would be started with, minus secrets.
+#[tauri::command]
+#[tracing::instrument(skip(state))]
+pub fn get_env_vars(state: State<'_, EnvOverrides>) -> HashMap<String, String> {
+    let overrides = state.0.lock().unwrap_or_else(|e| e.into_inner());
+    let mut vars: HashMap<String, String> = std::env::vars().collect();
+    vars.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+    vars.retain(|key, _| !HIDDEN_VARS.contains(&key.as_str()));
+    vars
+}
+
+/// Set `key` to `value` for the core, replacing any previous override.
+#[tauri::command]
+#[tracing::instrument(skip(app, state, value))]
+pub fn set_env_override<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, EnvOverrides>,
+    key: String,
+    value: String,
+) -> Result<(), MistError> {
+    if key.is_empty() || key.contains(['=', '\0']) {
+        return Err(MistError::Validation(format!(
+            "invalid variable name {key:?}"
+        )));
+    }
+    if value.contains('\0') {
+        return Err(MistError::Validation("value must not contain NUL".into()));
+    }
+    let mut overrides = state.0.lock()?;
+    config::update(&app, |doc| {
+        let section = doc["env"].or_insert(toml_edit::table());
+        section[&key] = toml_edit::value(&value);
+    })?;
+    overrides.insert(key, value);
+    Ok(())
+}
+
+/// Drop the override for `key`, if any.
+#[tauri::command]
+#[tracing::instrument(skip(app, state))]
+pub fn clear_env_override<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, EnvOverrides>,
+    key: String,
+) -> Result<(), MistError> {
+    let mut overrides = state.0.lock()?;
+    config::update(&app, |doc| {
+        if let Some(section) = doc.get_mut("env").and_then(|env| env.as_table_like_mut()) {
+            section.remove(&key);
+        }
+    })?;
+    overrides.remove(&key);
+    Ok(())
+}
+
+/// Drop every override.
+#[tauri::command]
+#[tracing::instrument(skip(app, state))]
+pub fn reset_env_overrides<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, EnvOverrides>,
+) -> Result<(), MistError> {
+    let mut overrides = state.0.lock()?;
+    config::update(&app, |doc| {
+        doc.remove("env");
+    })?;
+    overrides.clear();
+    Ok(())
+}
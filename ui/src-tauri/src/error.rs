@@ -0,0 +1,113 @@
+// Error type returned by every Tauri command.
+//
+// Serialises as `{ "type": "<variant>", "message": "<text>" }` so the
+// frontend can branch on `error.type` instead of matching on strings.
+
+use std::fmt;
+use std::io;
+use std::sync::PoisonError;
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MistError {
+    /// Reading or writing a file, pipe, or socket failed.
+    Io(String),
+    /// The core, another subprocess, or the Tauri runtime failed.
+    Process(String),
+    /// A settings file is unreadable or malformed.
+    Config(String),
+    /// The caller passed an argument that was rejected.
+    Validation(String),
+    /// The operation was cancelled before it finished.
+    Cancelled,
+    /// The operation did not finish in time.
+    Timeout,
+}
+
+impl MistError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "Io",
+            Self::Process(_) => "Process",
+            Self::Config(_) => "Config",
+            Self::Validation(_) => "Validation",
+            Self::Cancelled => "Cancelled",
+            Self::Timeout => "Timeout",
+        }
+    }
+}
+
+impl fmt::Display for MistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(message)
+            | Self::Process(message)
+            | Self::Config(message)
+            | Self::Validation(message) => f.write_str(message),
+            Self::Cancelled => f.write_str("cancelled"),
+            Self::Timeout => f.write_str("timeout"),
+        }
+    }
+}
+
+impl std::error::Error for MistError {}
+
+impl Serialize for MistError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("MistError", 2)?;
+        error.serialize_field("type", self.kind())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.end()
+    }
+}
+
+impl From<io::Error> for MistError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e.to_string())
+    }
+}
+
+impl From<toml::de::Error> for MistError {
+    fn from(e: toml::de::Error) -> Self {
+        Self::Config(format!("invalid config: {e}"))
+    }
+}
+
+impl From<tauri::Error> for MistError {
+    fn from(e: tauri::Error) -> Self {
+        Self::Process(e.to_string())
+    }
+}
+
+/// A lock is only poisoned if a thread panicked while holding it.
+impl<T> From<PoisonError<T>> for MistError {
+    fn from(e: PoisonError<T>) -> Self {
+        Self::Process(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn io_error_serialises_with_type_tag() {
+        let err = MistError::from(io::Error::new(io::ErrorKind::NotFound, "no such file"));
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({ "type": "Io", "message": "no such file" })
+        );
+    }
+
+    #[test]
+    fn unit_variants_still_carry_a_message() {
+        assert_eq!(
+            serde_json::to_value(MistError::Cancelled).unwrap(),
+            json!({ "type": "Cancelled", "message": "cancelled" })
+        );
+    }
+}
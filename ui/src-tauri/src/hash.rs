@@ -0,0 +1,181 @@
+// File checksums, so users can verify large inputs before handing them
+// to the core.
+//
+// Hashing runs on the blocking pool and streams the file in fixed-size
+// chunks. Progress goes out on `hash://progress` at most every 100 ms,
+// and `cancel_hash` stops the job at the next chunk boundary.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Runtime, State};
+use tokio_util::sync::CancellationToken;
+
+use crate::error::MistError;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Cancellation token for the most recent `hash_file` call.
+#[derive(Default)]
+pub struct HashState {
+    cancel: Mutex<CancellationToken>,
+}
+
+#[derive(Clone, Serialize)]
+struct Progress {
+    bytes_processed: u64,
+    total_bytes: u64,
+}
+
+enum Hasher {
+    Sha256(Sha256),
+    Md5(md5::Context),
+}
+
+impl Hasher {
+    fn new(algorithm: &str) -> Result<Self, MistError> {
+        match algorithm {
+            "sha256" => Ok(Self::Sha256(Sha256::new())),
+            "md5" => Ok(Self::Md5(md5::Context::new())),
+            other => Err(MistError::Validation(format!(
+                "unsupported hash algorithm {other:?}"
+            ))),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Sha256(hasher) => hasher.update(data),
+            Self::Md5(context) => context.consume(data),
+        }
+    }
+
+    fn hex(self) -> String {
+        match self {
+            Self::Sha256(hasher) => hasher
+                .finalize()
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect(),
+            Self::Md5(context) => format!("{:x}", context.finalize()),
+        }
+    }
+}
+
+/// Lowercase hex digest of the file at `path`. `algorithm` is `"sha256"`
+/// or `"md5"`.
+#[tauri::command]
+#[tracing::instrument(skip(app, state))]
+pub async fn hash_file<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, HashState>,
+    path: String,
+    algorithm: String,
+) -> Result<String, MistError> {
+    let hasher = Hasher::new(&algorithm)?;
+    let token = CancellationToken::new();
+    *state.cancel.lock()? = token.clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let file =
+            File::open(&path).map_err(|e| MistError::Io(format!("failed to open {path}: {e}")))?;
+        let total_bytes = file.metadata().map(|m| m.len()).unwrap_or(0);
+        let mut last_emit = Instant::now();
+        digest(file, hasher, &token, |bytes_processed| {
+            if last_emit.elapsed() >= PROGRESS_INTERVAL {
+                last_emit = Instant::now();
+                let _ = app.emit(
+                    "hash://progress",
+                    Progress {
+                        bytes_processed,
+                        total_bytes,
+                    },
+                );
+            }
+        })
+        .map_err(|e| MistError::Io(format!("failed to read {path}: {e}")))?
+        .ok_or(MistError::Cancelled)
+    })
+    .await?
+}
+
+/// Abort the `hash_file` call in progress, if any.
+#[tauri::command]
+#[tracing::instrument(skip(state))]
+pub fn cancel_hash(state: State<'_, HashState>) -> Result<(), MistError> {
+    state.cancel.lock()?.cancel();
+    Ok(())
+}
+
+/// Lowercase SHA-256 hex digest of everything `reader` yields.
+pub fn sha256(reader: impl Read) -> io::Result<String> {
+    let hasher = Hasher::Sha256(Sha256::new());
+    digest(reader, hasher, &CancellationToken::new(), |_| {}).map(Option::unwrap_or_default)
+}
+
+/// Feed `reader` through `hasher` chunk by chunk. Returns `None` if
+/// `token` is cancelled first.
+fn digest(
+    mut reader: impl Read,
+    mut hasher: Hasher,
+    token: &CancellationToken,
+    mut progress: impl FnMut(u64),
+) -> io::Result<Option<String>> {
+    let mut buf = vec![0; CHUNK_SIZE];
+    let mut processed = 0u64;
+    loop {
+        if token.is_cancelled() {
+            return Ok(None);
+        }
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        hasher.update(&buf[..n]);
+        processed += n as u64;
+        progress(processed);
+    }
+    Ok(Some(hasher.hex()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(algorithm: &str, data: &[u8]) -> Option<String> {
+        let hasher = Hasher::new(algorithm).unwrap();
+        digest(data, hasher, &CancellationToken::new(), |_| {}).unwrap()
+    }
+
+    #[test]
+    fn known_digests() {
+        assert_eq!(
+            hash("sha256", b"abc").unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hash("md5", b"abc").unwrap(),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_algorithm() {
+        assert!(Hasher::new("crc32").is_err());
+    }
+
+    #[test]
+    fn stops_when_cancelled() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let hasher = Hasher::new("sha256").unwrap();
+        assert_eq!(digest(&b"abc"[..], hasher, &token, |_| {}).unwrap(), None);
+    }
+}
@@ -0,0 +1,210 @@
+// Outbound HTTP on behalf of the frontend, which cannot call
+// authenticated external APIs itself because of CORS.
+//
+// Only URLs under a prefix listed in `[http_allowlist] prefixes` are
+// fetched. Redirects are not followed, so a redirect to an unlisted URL
+// reaches the caller as a 3xx response rather than being fetched.
+// Requests time out after 30 seconds and response bodies are capped at
+// 4 MB.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::redirect::Policy;
+use reqwest::{Method, Url};
+use serde::Serialize;
+use tauri::{AppHandle, Runtime, State};
+
+use crate::{config, download, error::MistError};
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+/// HTTP client shared by all requests, built on first use.
+#[derive(Default)]
+pub struct HttpClient(Mutex<Option<reqwest::Client>>);
+
+impl HttpClient {
+    fn get(&self) -> Result<reqwest::Client, MistError> {
+        let mut client = self.0.lock()?;
+        if let Some(client) = client.as_ref() {
+            return Ok(client.clone());
+        }
+        let built = download::client_builder()
+            .redirect(Policy::none())
+            .timeout(TIMEOUT)
+            .build()
+            .map_err(|e| MistError::Io(e.to_string()))?;
+        Ok(client.insert(built).clone())
+    }
+}
+
+/// Status, headers and body of a response. Repeated headers are joined
+/// with `", "`; a body that is not UTF-8 is decoded lossily.
+#[derive(Debug, Serialize)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+/// `GET url` with `headers`.
+#[tauri::command]
+#[tracing::instrument(skip(app, client, headers))]
+pub async fn http_get<R: Runtime>(
+    app: AppHandle<R>,
+    client: State<'_, HttpClient>,
+    url: String,
+    headers: HashMap<String, String>,
+) -> Result<HttpResponse, MistError> {
+    send(&app, &client, Method::GET, &url, headers, None).await
+}
+
+/// `POST url` with `headers` and `body`.
+#[tauri::command]
+#[tracing::instrument(skip(app, client, headers, body))]
+pub async fn http_post<R: Runtime>(
+    app: AppHandle<R>,
+    client: State<'_, HttpClient>,
+    url: String,
+    headers: HashMap<String, String>,
+    body: String,
+) -> Result<HttpResponse, MistError> {
+    send(&app, &client, Method::POST, &url, headers, Some(body)).await
+}
+
+async fn send<R: Runtime>(
+    app: &AppHandle<R>,
+    client: &HttpClient,
+    method: Method,
+    url: &str,
+    headers: HashMap<String, String>,
+    body: Option<String>,
+) -> Result<HttpResponse, MistError> {
+    let url =
+        Url::parse(url).map_err(|e| MistError::Validation(format!("invalid URL {url:?}: {e}")))?;
+    if !allowed(&url, &allowlist(app)?) {
+        return Err(MistError::Validation("URL not in allowlist".into()));
+    }
+
+    let mut request = client
+        .get()?
+        .request(method, url)
+        .headers(header_map(headers)?);
+    if let Some(body) = body {
+        request = request.body(body);
+    }
+    let mut response = request.send().await.map_err(request_error)?;
+
+    let status = response.status().as_u16();
+    let mut headers: HashMap<String, String> = HashMap::new();
+    for (name, value) in response.headers() {
+        let value = String::from_utf8_lossy(value.as_bytes());
+        headers
+            .entry(name.as_str().to_string())
+            .and_modify(|joined| {
+                joined.push_str(", ");
+                joined.push_str(&value);
+            })
+            .or_insert_with(|| value.into_owned());
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(request_error)? {
+        if body.len() + chunk.len() > MAX_BODY_BYTES {
+            return Err(MistError::Io(format!(
+                "response body is larger than {} MB",
+                MAX_BODY_BYTES / (1024 * 1024)
+            )));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(HttpResponse {
+        status,
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+fn allowlist<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<String>, MistError> {
+    let config = config::load(app)?;
+    Ok(config
+        .get("http_allowlist")
+        .and_then(|section| section.get("prefixes"))
+        .and_then(|prefixes| prefixes.as_array())
+        .map(|prefixes| {
+            prefixes
+                .iter()
+                .filter_map(|prefix| prefix.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Whether `url` falls under one of `prefixes`. A prefix must end on a
+/// path boundary, so `https://api.example.com` does not admit
+/// `https://api.example.com.evil.io`.
+fn allowed(url: &Url, prefixes: &[String]) -> bool {
+    let url = url.as_str();
+    prefixes.iter().any(|prefix| {
+        url.strip_prefix(prefix.as_str()).is_some_and(|rest| {
+            prefix.ends_with('/') || rest.is_empty() || rest.starts_with(['/', '?', '#'])
+        })
+    })
+}
+
+fn header_map(headers: HashMap<String, String>) -> Result<HeaderMap, MistError> {
+    let mut map = HeaderMap::with_capacity(headers.len());
+    for (name, value) in headers {
+        let header = HeaderName::try_from(name.as_str())
+            .map_err(|_| MistError::Validation(format!("invalid header name {name:?}")))?;
+        let value = HeaderValue::try_from(value)
+            .map_err(|_| MistError::Validation(format!("invalid value for header {name:?}")))?;
+        map.insert(header, value);
+    }
+    Ok(map)
+}
+
+fn request_error(e: reqwest::Error) -> MistError {
+    if e.is_timeout() {
+        MistError::Timeout
+    } else {
+        MistError::Io(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_allowed(url: &str, prefixes: &[&str]) -> bool {
+        let prefixes: Vec<_> = prefixes.iter().map(|p| p.to_string()).collect();
+        allowed(&Url::parse(url).unwrap(), &prefixes)
+    }
+
+    #[test]
+    fn allowlist_matches_on_boundaries() {
+        let prefixes = ["https://api.example.com", "https://data.example.org/v2/"];
+        assert!(is_allowed("https://api.example.com", &prefixes));
+        assert!(is_allowed("https://api.example.com/runs?page=2", &prefixes));
+        assert!(is_allowed("https://data.example.org/v2/runs", &prefixes));
+
+        assert!(!is_allowed("https://api.example.com.evil.io/", &prefixes));
+        assert!(!is_allowed("https://api.example.com@evil.io/", &prefixes));
+        assert!(!is_allowed("https://data.example.org/v1/runs", &prefixes));
+        assert!(!is_allowed("http://api.example.com/", &prefixes));
+        assert!(!is_allowed("https://api.example.com/", &[]));
+    }
+
+    #[test]
+    fn rejects_invalid_headers() {
+        let headers = HashMap::from([("Bad Header".to_string(), "x".to_string())]);
+        assert!(matches!(header_map(headers), Err(MistError::Validation(_))));
+
+        let headers = HashMap::from([("Authorization".to_string(), "Bearer abc".to_string())]);
+        let map = header_map(headers).unwrap();
+        assert_eq!(map["authorization"], "Bearer abc");
+    }
+}
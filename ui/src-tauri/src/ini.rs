@@ -0,0 +1,267 @@
+// Legacy `.ini` settings from MIST 1.x, which wrote them with Python's
+// configparser, and their migration to TOML.
+//
+// Parsing follows configparser's defaults: `#` and `;` only start
+// full-line comments, so values may contain either; `=` or `:` separate
+// keys from values; and a value continues on lines indented deeper than
+// its key. Keys and section names keep their case, and a key without a
+// value reads as an empty string. Entries before the first section header
+// live in the `""` section.
+
+use std::collections::HashMap;
+use std::fs;
+
+use configparser::ini::{Ini, IniDefault, WriteOptions};
+
+use crate::error::MistError;
+
+/// Section → key → value. The `""` section holds the global entries.
+pub type IniData = HashMap<String, HashMap<String, String>>;
+
+const GLOBAL_SECTION: &str = "";
+
+/// Read the INI file at `path`.
+#[tauri::command]
+#[tracing::instrument]
+pub fn read_ini_config(path: String) -> Result<IniData, MistError> {
+    let text = fs::read_to_string(&path)
+        .map_err(|e| MistError::Io(format!("failed to read {path}: {e}")))?;
+    parse(&text).map_err(|e| MistError::Config(format!("invalid {path}: {e}")))
+}
+
+/// Write `data` to `path` as INI, with sections and keys sorted and the
+/// global entries first.
+#[tauri::command]
+#[tracing::instrument(skip(data))]
+pub fn write_ini_config(path: String, data: IniData) -> Result<(), MistError> {
+    fs::write(&path, render(&data)?)
+        .map_err(|e| MistError::Io(format!("failed to write {path}: {e}")))
+}
+
+/// Convert the INI file at `ini_path` to TOML at `toml_path`. Global
+/// entries become top-level keys and sections become tables; all values
+/// are strings.
+#[tauri::command]
+#[tracing::instrument]
+pub fn convert_ini_to_toml(ini_path: String, toml_path: String) -> Result<(), MistError> {
+    let data = read_ini_config(ini_path)?;
+    fs::write(&toml_path, to_toml(&data)?.to_string())
+        .map_err(|e| MistError::Io(format!("failed to write {toml_path}: {e}")))
+}
+
+fn parser() -> Ini {
+    let mut defaults = IniDefault::default();
+    defaults.default_section = GLOBAL_SECTION.into();
+    defaults.case_sensitive = true;
+    defaults.multiline = true;
+    defaults.enable_inline_comments = false;
+    Ini::new_from_defaults(defaults)
+}
+
+fn parse(text: &str) -> Result<IniData, String> {
+    let sections = parser().read(text.into())?;
+    Ok(sections
+        .into_iter()
+        .map(|(section, entries)| {
+            let entries = entries
+                .into_iter()
+                .map(|(key, value)| (key, value.unwrap_or_default().replace("\r\n", "\n")))
+                .collect();
+            (section, entries)
+        })
+        .collect())
+}
+
+fn render(data: &IniData) -> Result<String, MistError> {
+    let mut ini = parser();
+    for (section, entries) in sorted(data) {
+        check_section(section)?;
+        // Keep sections that have no keys.
+        ini.get_mut_map().entry(section.clone()).or_default();
+        for (key, value) in sorted(entries) {
+            check_entry(section, key, value)?;
+            ini.set(section, key, Some(value.clone()));
+        }
+    }
+    Ok(ini.pretty_writes(&WriteOptions::new_with_params(true, 4, 1)))
+}
+
+fn to_toml(data: &IniData) -> Result<toml_edit::DocumentMut, MistError> {
+    let mut doc = toml_edit::DocumentMut::new();
+    for (section, entries) in sorted(data) {
+        if section == GLOBAL_SECTION {
+            for (key, value) in sorted(entries) {
+                doc[key.as_str()] = toml_edit::value(value.as_str());
+            }
+            continue;
+        }
+        if data
+            .get(GLOBAL_SECTION)
+            .is_some_and(|globals| globals.contains_key(section))
+        {
+            return Err(MistError::Validation(format!(
+                "global key {section} clashes with section [{section}]"
+            )));
+        }
+        let mut table = toml_edit::Table::new();
+        for (key, value) in sorted(entries) {
+            table[key.as_str()] = toml_edit::value(value.as_str());
+        }
+        doc[section.as_str()] = toml_edit::Item::Table(table);
+    }
+    Ok(doc)
+}
+
+fn sorted<V>(map: &HashMap<String, V>) -> Vec<(&String, &V)> {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_by_key(|(key, _)| *key);
+    entries
+}
+
+fn check_section(section: &str) -> Result<(), MistError> {
+    if section.contains([']', '\n', '\r']) || section.trim() != section {
+        return Err(MistError::Validation(format!(
+            "section name {section:?} cannot be written to INI"
+        )));
+    }
+    Ok(())
+}
+
+/// Reject entries that would read back differently, such as keys holding
+/// a delimiter or continuation lines that look like comments.
+fn check_entry(section: &str, key: &str, value: &str) -> Result<(), MistError> {
+    let key_ok = !key.is_empty()
+        && key.trim() == key
+        && !key.contains(['=', ':', '\n', '\r'])
+        && !key.starts_with(['[', '#', ';']);
+    let value_ok = value.trim() == value
+        && value
+            .lines()
+            .enumerate()
+            .all(|(i, line)| line.trim() == line && (i == 0 || !line.starts_with(['[', '#', ';'])));
+    if !key_ok || !value_ok {
+        return Err(MistError::Validation(format!(
+            "entry {key:?} in section {section:?} cannot be written to INI"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(sections: &[(&str, &[(&str, &str)])]) -> IniData {
+        sections
+            .iter()
+            .map(|(section, entries)| {
+                let entries = entries
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect();
+                (section.to_string(), entries)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reads_configparser_files() {
+        let text = "\
+# written by MIST 1.4
+version = 1.4
+[Core]
+DataDir : C:\\Users\\me\\mist
+url = http://localhost/#status ; not a comment
+; a comment
+verbose
+
+[Plugins]
+enabled = reader
+    writer
+
+    # skipped
+    plotter
+[Empty]
+";
+        assert_eq!(
+            parse(text).unwrap(),
+            data(&[
+                ("", &[("version", "1.4")]),
+                (
+                    "Core",
+                    &[
+                        ("DataDir", "C:\\Users\\me\\mist"),
+                        ("url", "http://localhost/#status ; not a comment"),
+                        ("verbose", ""),
+                    ]
+                ),
+                ("Plugins", &[("enabled", "reader\nwriter\n\nplotter")]),
+                ("Empty", &[]),
+            ])
+        );
+    }
+
+    #[test]
+    fn round_trips_through_write() {
+        let original = data(&[
+            ("", &[("version", "2"), ("name", "MIST")]),
+            (
+                "Core",
+                &[
+                    ("DataDir", "C:\\Users\\me\\mist"),
+                    ("url", "http://localhost:8765/#a;b"),
+                    ("empty", ""),
+                ],
+            ),
+            ("Plugins", &[("enabled", "reader\nwriter\n\nplotter")]),
+            ("Empty", &[]),
+        ]);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mist.ini").to_string_lossy().into_owned();
+        write_ini_config(path.clone(), original.clone()).unwrap();
+        assert_eq!(read_ini_config(path).unwrap(), original);
+    }
+
+    #[test]
+    fn rejects_entries_that_would_not_round_trip() {
+        for (section, key, value) in [
+            ("Core", "a=b", "x"),
+            ("Core", " padded", "x"),
+            ("Core", "#key", "x"),
+            ("Core", "key", " padded"),
+            ("Core", "key", "first\n# second"),
+            ("Core", "key", "first\n  second"),
+            ("Co]re", "key", "x"),
+        ] {
+            let data = data(&[(section, &[(key, value)])]);
+            assert!(
+                matches!(render(&data), Err(MistError::Validation(_))),
+                "{section} {key} {value:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn converts_to_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let ini = dir.path().join("mist.ini");
+        let toml = dir.path().join("config.toml");
+        fs::write(
+            &ini,
+            "version = 1\n[core]\nport = 8765\n[project]\nname = demo\n",
+        )
+        .unwrap();
+        convert_ini_to_toml(
+            ini.to_string_lossy().into_owned(),
+            toml.to_string_lossy().into_owned(),
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(&toml).unwrap(),
+            "version = \"1\"\n\n[core]\nport = \"8765\"\n\n[project]\nname = \"demo\"\n"
+        );
+
+        let clash = data(&[("", &[("core", "x")]), ("core", &[("port", "1")])]);
+        assert!(matches!(to_toml(&clash), Err(MistError::Validation(_))));
+    }
+}
@@ -0,0 +1,135 @@
+// Application logging and log file access.
+//
+// `init` runs before the Tauri builder so that builder warnings land in
+// the log too. That means the log directory is resolved by hand rather
+// than through `PathResolver`; it mirrors `app_log_dir()` except on
+// Windows, where logs live in `%APPDATA%\mist\logs`.
+
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_opener::OpenerExt;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+use crate::error::MistError;
+
+const LOG_PREFIX: &str = "mist";
+const LOG_SUFFIX: &str = "log";
+const MAX_LOG_FILES: usize = 7;
+
+/// Directory holding the rolling log files for the app `identifier`.
+pub fn log_dir(identifier: &str) -> Option<PathBuf> {
+    #[cfg(windows)]
+    let dir = {
+        let _ = identifier;
+        dirs::config_dir().map(|dir| dir.join("mist").join("logs"))
+    };
+
+    #[cfg(target_os = "macos")]
+    let dir = dirs::home_dir().map(|dir| dir.join("Library/Logs").join(identifier));
+
+    #[cfg(not(any(windows, target_os = "macos")))]
+    let dir = dirs::data_local_dir().map(|dir| dir.join(identifier).join("logs"));
+
+    dir
+}
+
+/// Install the global subscriber: JSON to a daily-rolling file, INFO and
+/// above to stderr. The returned guard flushes the file writer on drop and
+/// must be held for the lifetime of the app.
+pub fn init(identifier: &str) -> Option<WorkerGuard> {
+    let stderr = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_filter(LevelFilter::INFO);
+
+    let appender = log_dir(identifier).and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_PREFIX)
+            .filename_suffix(LOG_SUFFIX)
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+            .map_err(|e| eprintln!("file logging disabled: {e}"))
+            .ok()
+    });
+
+    match appender {
+        Some(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let file = tracing_subscriber::fmt::layer()
+                .json()
+                .with_writer(writer)
+                .with_filter(LevelFilter::DEBUG);
+            tracing_subscriber::registry()
+                .with(stderr)
+                .with(file)
+                .init();
+            Some(guard)
+        }
+        None => {
+            tracing_subscriber::registry().with(stderr).init();
+            None
+        }
+    }
+}
+
+/// Open the current log file in the system's default viewer, creating it
+/// if needed.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub fn open_log_file<R: Runtime>(app: AppHandle<R>) -> Result<(), MistError> {
+    let path = log_path(&app)?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| MistError::Io(format!("failed to create {}: {e}", path.display())))?;
+    app.opener()
+        .open_path(path.to_string_lossy(), None::<&str>)
+        .map_err(|e| MistError::Process(e.to_string()))
+}
+
+/// Absolute path of the current log file, for display in the UI.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub fn get_log_path<R: Runtime>(app: AppHandle<R>) -> Result<String, MistError> {
+    log_path(&app).map(|p| p.to_string_lossy().into_owned())
+}
+
+fn log_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, MistError> {
+    let dir = log_dir(&app.config().identifier)
+        .ok_or_else(|| MistError::Io("no log directory on this platform".into()))?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| MistError::Io(format!("failed to create {}: {e}", dir.display())))?;
+    Ok(latest_log(&dir).unwrap_or_else(|| dir.join(format!("{LOG_PREFIX}.{LOG_SUFFIX}"))))
+}
+
+/// Most recently modified rolling log file in `dir`.
+fn latest_log(dir: &Path) -> Option<PathBuf> {
+    log_files(dir)
+        .ok()?
+        .into_iter()
+        .max_by_key(|path| path.metadata().and_then(|m| m.modified()).ok())
+}
+
+/// The rolling log files in `dir`, sorted by name.
+pub fn log_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with(LOG_PREFIX) && name.ends_with(LOG_SUFFIX) {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
@@ -1,12 +1,220 @@
 // MIST UI — Tauri shell
 // The frontend connects directly to the Python core via WebSocket.
 // This Rust backend provides the native window and can optionally
-// manage the core process lifecycle.
+// manage the core process lifecycle (see `core_process`).
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod archive;
+mod bundle;
+mod clipboard;
+mod config;
+mod core_process;
+mod csv_preview;
+mod deep_link;
+mod devtools;
+mod diagnostics;
+mod dialog;
+mod diff;
+mod download;
+mod env;
+mod error;
+mod hash;
+mod http;
+mod ini;
+mod logs;
+mod net;
+mod notify;
+mod plugins;
+mod power;
+mod python_env;
+mod recent;
+mod rendering;
+mod sandbox;
+mod scheduler;
+mod screenshot;
+mod shortcuts;
+mod snippet;
+mod sqlite;
+mod system;
+mod tail;
+mod temp;
+#[cfg(test)]
+mod test_util;
+mod tray;
+mod updater;
+mod window;
+mod zoom;
+
+use tauri::{Manager, RunEvent, WindowEvent};
+
 fn main() {
+    let context = tauri::generate_context!();
+    let _log_guard = logs::init(&context.config().identifier);
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "MIST starting");
+
     tauri::Builder::default()
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        // Must be registered before any other plugin.
+        .plugin(tauri_plugin_single_instance::init(
+            window::on_second_instance,
+        ))
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(mist_plugin_ipc_bridge::init(
+            mist_plugin_ipc_bridge::PluginConfig::default(),
+        ))
+        .manage(core_process::CoreState::default())
+        .manage(config::ConfigLock::default())
+        .manage(updater::UpdateState::default())
+        .manage(notify::RecentNotifications::default())
+        .manage(power::WakeLockState::default())
+        .manage(hash::HashState::default())
+        .manage(tail::FileWatchers::default())
+        .manage(plugins::PluginRegistry::default())
+        .manage(env::EnvOverrides::default())
+        .manage(python_env::PythonEnvCache::default())
+        .manage(recent::RecentFiles::default())
+        .manage(download::Downloads::default())
+        .manage(shortcuts::Shortcuts::default())
+        .manage(window::AlwaysOnTop::default())
+        .manage(scheduler::ScheduledTasks::default())
+        .manage(http::HttpClient::default())
+        .manage(zoom::ZoomLevel::default())
+        .manage(sandbox::SandboxState::default())
+        .setup(|app| {
+            rendering::create_main_window(app.handle())?;
+            tray::init(app.handle())?;
+            deep_link::init(app.handle())?;
+            if let Err(e) = env::init(app.handle()) {
+                tracing::warn!("could not load environment overrides: {e}");
+            }
+            if let Err(e) = plugins::init(app.handle()) {
+                tracing::warn!("could not restore plugins: {e}");
+            }
+            if let Err(e) = recent::init(app.handle()) {
+                tracing::warn!("could not restore recent files: {e}");
+            }
+            if let Err(e) = shortcuts::init(app.handle()) {
+                tracing::warn!("could not restore shortcuts: {e}");
+            }
+            if let Err(e) = window::restore_bounds(app.handle()) {
+                tracing::warn!("could not restore window bounds: {e}");
+            }
+            if let Err(e) = window::restore_always_on_top(app.handle()) {
+                tracing::warn!("could not restore always on top: {e}");
+            }
+            if let Err(e) = zoom::init(app.handle()) {
+                tracing::warn!("could not restore zoom level: {e}");
+            }
+            if let Err(e) = window::restore_title(app.handle()) {
+                tracing::warn!("could not set window title: {e}");
+            }
+            tracing::info!("main window ready");
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            core_process::start_core,
+            core_process::restart_core,
+            core_process::get_core_status,
+            core_process::get_core_port,
+            core_process::send_to_core,
+            config::read_config,
+            config::write_config,
+            dialog::pick_directory,
+            dialog::pick_directory_async,
+            logs::open_log_file,
+            logs::get_log_path,
+            net::check_port_available,
+            system::get_system_info,
+            temp::write_temp_file,
+            temp::cleanup_temp_files,
+            updater::check_for_update,
+            updater::install_update,
+            updater::cancel_update,
+            clipboard::copy_to_clipboard,
+            clipboard::read_clipboard,
+            notify::request_notification_permission,
+            notify::send_native_notification,
+            window::get_window_bounds,
+            window::set_window_bounds,
+            window::get_available_monitors,
+            window::move_window_to_monitor,
+            window::set_window_title,
+            window::set_title_template,
+            window::resolve_title,
+            window::window_always_on_top,
+            power::prevent_sleep,
+            power::allow_sleep,
+            hash::hash_file,
+            hash::cancel_hash,
+            tail::tail_file,
+            tail::watch_file,
+            tail::unwatch_file,
+            snippet::run_python_snippet,
+            plugins::load_plugin,
+            plugins::unload_plugin,
+            plugins::list_plugins,
+            env::get_env_vars,
+            env::set_env_override,
+            env::clear_env_override,
+            env::reset_env_overrides,
+            screenshot::window_screenshot,
+            screenshot::save_screenshot,
+            python_env::validate_python_environment,
+            recent::add_recent_file,
+            recent::get_recent_files,
+            recent::clear_recent_files,
+            download::download_file,
+            download::cancel_download,
+            shortcuts::set_global_shortcut,
+            shortcuts::remove_global_shortcut,
+            shortcuts::list_global_shortcuts,
+            diff::compute_file_diff,
+            diff::diff_strings,
+            archive::compress_directory,
+            archive::extract_archive,
+            sqlite::execute_sql,
+            diagnostics::export_diagnostics_bundle,
+            scheduler::schedule_task,
+            scheduler::cancel_task,
+            scheduler::list_tasks,
+            http::http_get,
+            http::http_post,
+            csv_preview::parse_csv,
+            bundle::verify_bundle_integrity,
+            bundle::get_bundle_manifest,
+            zoom::set_zoom_level,
+            zoom::get_zoom_level,
+            devtools::open_devtools,
+            devtools::close_devtools,
+            devtools::is_devtools_open,
+            ini::read_ini_config,
+            ini::write_ini_config,
+            ini::convert_ini_to_toml,
+            rendering::set_rendering_hints,
+            sandbox::get_sandbox_status,
+            shortcuts::keyboard_shortcut_cheatsheet,
+        ])
+        .on_window_event(|window, event| {
+            if window.label() != window::MAIN {
+                return;
+            }
+            match event {
+                WindowEvent::CloseRequested { api, .. } => window::on_close_requested(window, api),
+                WindowEvent::Destroyed => core_process::shutdown(window.app_handle()),
+                _ => {}
+            }
+        })
+        .build(context)
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                power::release(app);
+            }
+        });
 }
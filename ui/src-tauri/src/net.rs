@@ -0,0 +1,44 @@
+// Local network helpers.
+
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, TcpListener};
+
+use crate::error::MistError;
+
+/// Check that `port` is free on the loopback interface.
+///
+/// Returns the port if it is available, or `None` if something is already
+/// listening on it. Passing `0` asks the OS for a free port and returns the
+/// one it picked. Any other bind failure is an error.
+#[tauri::command]
+#[tracing::instrument]
+pub fn check_port_available(port: u16) -> Result<Option<u16>, MistError> {
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, port)) {
+        Ok(listener) => listener,
+        Err(e) if e.kind() == ErrorKind::AddrInUse => return Ok(None),
+        Err(e) => return Err(MistError::Io(format!("cannot bind port {port}: {e}"))),
+    };
+    let bound = listener.local_addr()?;
+    Ok(Some(bound.port()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_port_in_use() {
+        let taken = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = taken.local_addr().unwrap().port();
+        assert_eq!(check_port_available(port).unwrap(), None);
+
+        drop(taken);
+        assert_eq!(check_port_available(port).unwrap(), Some(port));
+    }
+
+    #[test]
+    fn zero_picks_a_free_port() {
+        let port = check_port_available(0).unwrap().unwrap();
+        assert_ne!(port, 0);
+    }
+}
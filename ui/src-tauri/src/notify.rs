@@ -0,0 +1,64 @@
+// Native OS notifications for when the window is hidden or minimised.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tauri::path::BaseDirectory;
+use tauri::{AppHandle, Manager, Runtime, State};
+use tauri_plugin_notification::{NotificationExt, PermissionState};
+
+use crate::error::MistError;
+
+/// Repeat notifications with the same identifier inside this window are dropped.
+const DEDUP_WINDOW: Duration = Duration::from_secs(2);
+
+/// Bundled resource shown as the notification icon.
+const ICON_RESOURCE: &str = "icons/icon.png";
+
+/// When each notification identifier was last shown.
+#[derive(Default)]
+pub struct RecentNotifications(Mutex<HashMap<String, Instant>>);
+
+/// Ask the OS for permission to show notifications. Returns whether it
+/// was granted.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub fn request_notification_permission<R: Runtime>(app: AppHandle<R>) -> Result<bool, MistError> {
+    let state = app
+        .notification()
+        .request_permission()
+        .map_err(|e| MistError::Process(e.to_string()))?;
+    Ok(state == PermissionState::Granted)
+}
+
+/// Show a native notification. A second call with the same `identifier`
+/// within two seconds is silently ignored.
+#[tauri::command]
+#[tracing::instrument(skip(app, recent, body))]
+pub fn send_native_notification<R: Runtime>(
+    app: AppHandle<R>,
+    recent: State<'_, RecentNotifications>,
+    identifier: String,
+    title: String,
+    body: String,
+) -> Result<(), MistError> {
+    {
+        let mut recent = recent.0.lock()?;
+        let now = Instant::now();
+        recent.retain(|_, shown| now.duration_since(*shown) < DEDUP_WINDOW);
+        if recent.contains_key(&identifier) {
+            tracing::debug!("dropping duplicate notification");
+            return Ok(());
+        }
+        recent.insert(identifier, now);
+    }
+
+    let mut builder = app.notification().builder().title(title).body(body);
+    if let Ok(icon) = app.path().resolve(ICON_RESOURCE, BaseDirectory::Resource) {
+        builder = builder.icon(icon.to_string_lossy());
+    }
+    builder
+        .show()
+        .map_err(|e| MistError::Process(e.to_string()))
+}
@@ -0,0 +1,211 @@
+// User-written Python plugins.
+//
+// A plugin is a single `.py` module. Its file stem is the plugin name, and
+// its `__version__` is read by importing it with the core's interpreter.
+// The registry is persisted to `plugins.json` in the app data directory
+// and reloaded at startup. Every change is emitted on `plugins://changed`
+// and sent to the core over the stdin channel as `plugin.load` or
+// `plugin.unload`. If the core is not running, that notification is
+// skipped with a warning.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::{core_process, error::MistError};
+
+const REGISTRY_FILE: &str = "plugins.json";
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PluginMeta {
+    pub name: String,
+    pub path: String,
+    pub version: String,
+    pub enabled: bool,
+}
+
+/// Loaded plugins, keyed by name.
+#[derive(Default)]
+pub struct PluginRegistry(Mutex<HashMap<String, PluginMeta>>);
+
+/// Restore the registry saved by a previous session.
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), MistError> {
+    let path = registry_path(app)?;
+    if !path.exists() {
+        return Ok(());
+    }
+    let raw = fs::read_to_string(&path)
+        .map_err(|e| MistError::Io(format!("failed to read {}: {e}", path.display())))?;
+    let plugins: Vec<PluginMeta> = serde_json::from_str(&raw)
+        .map_err(|e| MistError::Config(format!("invalid {}: {e}", path.display())))?;
+    let registry = app.state::<PluginRegistry>();
+    let mut guard = registry.0.lock()?;
+    guard.extend(plugins.into_iter().map(|p| (p.name.clone(), p)));
+    Ok(())
+}
+
+/// Register the plugin module at `path`, replacing any plugin of the same
+/// name.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub async fn load_plugin<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+) -> Result<PluginMeta, MistError> {
+    let file = PathBuf::from(&path);
+    if !file.is_file() {
+        return Err(MistError::Validation(format!("{path} is not a file")));
+    }
+    if file.extension().and_then(|ext| ext.to_str()) != Some("py") {
+        return Err(MistError::Validation(format!(
+            "{path} is not a Python module"
+        )));
+    }
+    let name = file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .filter(|stem| is_identifier(stem))
+        .ok_or_else(|| MistError::Validation(format!("{path} is not an importable module name")))?
+        .to_string();
+
+    let python = core_process::python_path(&app);
+    let module = name.clone();
+    let version =
+        tauri::async_runtime::spawn_blocking(move || read_version(&python, &file, &module))
+            .await??;
+
+    let meta = PluginMeta {
+        name,
+        path,
+        version,
+        enabled: true,
+    };
+    let registry = app.state::<PluginRegistry>();
+    let mut guard = registry.0.lock()?;
+    guard.insert(meta.name.clone(), meta.clone());
+    changed(&app, &guard)?;
+    notify_core(&app, "plugin.load", json!(meta));
+    tracing::info!(name = %meta.name, version = %meta.version, "plugin loaded");
+    Ok(meta)
+}
+
+/// Remove the plugin registered as `name`.
+#[tauri::command]
+#[tracing::instrument(skip(app, registry))]
+pub fn unload_plugin<R: Runtime>(
+    app: AppHandle<R>,
+    registry: State<'_, PluginRegistry>,
+    name: String,
+) -> Result<(), MistError> {
+    let mut guard = registry.0.lock()?;
+    guard
+        .remove(&name)
+        .ok_or_else(|| MistError::Validation(format!("no plugin named {name:?}")))?;
+    changed(&app, &guard)?;
+    notify_core(&app, "plugin.unload", json!({ "name": name }));
+    tracing::info!(%name, "plugin unloaded");
+    Ok(())
+}
+
+/// All registered plugins, sorted by name.
+#[tauri::command]
+#[tracing::instrument(skip(registry))]
+pub fn list_plugins(registry: State<'_, PluginRegistry>) -> Vec<PluginMeta> {
+    sorted(&registry.0.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Persist the registry and emit `plugins://changed` with its contents.
+fn changed<R: Runtime>(
+    app: &AppHandle<R>,
+    plugins: &HashMap<String, PluginMeta>,
+) -> Result<(), MistError> {
+    let plugins = sorted(plugins);
+    let path = registry_path(app)?;
+    let raw =
+        serde_json::to_string_pretty(&plugins).map_err(|e| MistError::Config(e.to_string()))?;
+    fs::write(&path, raw)
+        .map_err(|e| MistError::Io(format!("failed to write {}: {e}", path.display())))?;
+    let _ = app.emit("plugins://changed", plugins);
+    Ok(())
+}
+
+fn notify_core<R: Runtime>(app: &AppHandle<R>, kind: &str, payload: serde_json::Value) {
+    let msg = json!({
+        "type": kind,
+        "id": uuid::Uuid::new_v4().to_string(),
+        "from": "ui",
+        "to": "broker",
+        "payload": payload,
+    });
+    if let Err(e) = core_process::send_message(app, &msg) {
+        tracing::warn!("could not notify core of {kind}: {e}");
+    }
+}
+
+/// Import `module` from the directory containing `file` and return its
+/// `__version__`.
+fn read_version(python: &Path, file: &Path, module: &str) -> Result<String, MistError> {
+    let dir = file
+        .parent()
+        .ok_or_else(|| MistError::Validation("plugin has no parent directory".into()))?;
+    let output = Command::new(python)
+        .arg("-c")
+        .arg(format!("import {module}; print({module}.__version__)"))
+        // `-c` puts the working directory first on `sys.path`.
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| MistError::Process(format!("failed to spawn {}: {e}", python.display())))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().last().unwrap_or("unknown error");
+        return Err(MistError::Process(format!(
+            "failed to import {module}: {reason}"
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Whether `name` can be used verbatim in an `import` statement. Also
+/// keeps it from injecting anything into the `-c` snippet.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c == '_' || c.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
+
+fn sorted(plugins: &HashMap<String, PluginMeta>) -> Vec<PluginMeta> {
+    let mut plugins: Vec<_> = plugins.values().cloned().collect();
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    plugins
+}
+
+fn registry_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, MistError> {
+    let dir = app.path().app_data_dir()?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| MistError::Io(format!("failed to create {}: {e}", dir.display())))?;
+    Ok(dir.join(REGISTRY_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_names_must_be_identifiers() {
+        for name in ["plot", "_private", "fit2d"] {
+            assert!(is_identifier(name), "{name:?} should be accepted");
+        }
+        for name in ["", "2d", "my-plugin", "os; import shutil", "a.b"] {
+            assert!(!is_identifier(name), "{name:?} should be rejected");
+        }
+    }
+}
@@ -0,0 +1,227 @@
+// Keep the OS awake while long jobs run.
+//
+// Each platform has its own mechanism:
+// - Windows: `SetThreadExecutionState`, held by a parked helper thread
+//   since the flag is per-thread.
+// - macOS: an IOKit `PreventUserIdleSystemSleep` power assertion.
+// - Linux: a `systemd-inhibit` child that holds the inhibitor lock for as
+//   long as it lives.
+//
+// The lock is released when dropped, so clearing the state is enough.
+
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Runtime, State};
+
+use crate::error::MistError;
+
+/// The wake lock currently held, if any.
+#[derive(Default)]
+pub struct WakeLockState(Mutex<Option<WakeLock>>);
+
+pub struct WakeLock {
+    reason: String,
+    _held: platform::Held,
+}
+
+/// Stop the system from sleeping until `allow_sleep` is called. Calling it
+/// again while a lock is held only replaces the reason.
+#[tauri::command]
+#[tracing::instrument(skip(state))]
+pub fn prevent_sleep(state: State<'_, WakeLockState>, reason: String) -> Result<(), MistError> {
+    let mut guard = state.0.lock()?;
+    if let Some(lock) = guard.as_mut() {
+        tracing::warn!(previous = %lock.reason, "wake lock already held, updating reason");
+        lock.reason = reason;
+        return Ok(());
+    }
+    let held = platform::acquire(&reason)?;
+    tracing::info!("wake lock acquired");
+    *guard = Some(WakeLock {
+        reason,
+        _held: held,
+    });
+    Ok(())
+}
+
+/// Release the wake lock taken by `prevent_sleep`, if any.
+#[tauri::command]
+#[tracing::instrument(skip(state))]
+pub fn allow_sleep(state: State<'_, WakeLockState>) -> Result<(), MistError> {
+    if state.0.lock()?.take().is_some() {
+        tracing::info!("wake lock released");
+    }
+    Ok(())
+}
+
+/// Drop any held wake lock. Called on app exit.
+pub fn release<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(mut guard) = app.state::<WakeLockState>().0.lock() {
+        guard.take();
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::sync::mpsc;
+    use std::thread::{self, JoinHandle};
+
+    use windows_sys::Win32::System::Power::{
+        SetThreadExecutionState, ES_CONTINUOUS, ES_SYSTEM_REQUIRED,
+    };
+
+    use crate::error::MistError;
+
+    pub struct Held {
+        release: Option<mpsc::Sender<()>>,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    pub fn acquire(_reason: &str) -> Result<Held, MistError> {
+        let (release, wait) = mpsc::channel::<()>();
+        let (ready, acquired) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            let ok = unsafe { SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED) } != 0;
+            let _ = ready.send(ok);
+            if ok {
+                // Returns once the sender is dropped.
+                let _ = wait.recv();
+                unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+            }
+        });
+        match acquired.recv() {
+            Ok(true) => Ok(Held {
+                release: Some(release),
+                thread: Some(thread),
+            }),
+            _ => Err(MistError::Process("SetThreadExecutionState failed".into())),
+        }
+    }
+
+    impl Drop for Held {
+        fn drop(&mut self) {
+            self.release.take();
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::{c_char, c_void, CString};
+
+    use crate::error::MistError;
+
+    type CFStringRef = *const c_void;
+
+    const CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+    const IOPM_ASSERTION_LEVEL_ON: u32 = 255;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithCString(
+            alloc: *const c_void,
+            c_str: *const c_char,
+            encoding: u32,
+        ) -> CFStringRef;
+        fn CFRelease(cf: *const c_void);
+    }
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPMAssertionCreateWithName(
+            assertion_type: CFStringRef,
+            level: u32,
+            name: CFStringRef,
+            id: *mut u32,
+        ) -> i32;
+        fn IOPMAssertionRelease(id: u32) -> i32;
+    }
+
+    pub struct Held {
+        id: u32,
+    }
+
+    pub fn acquire(reason: &str) -> Result<Held, MistError> {
+        let kind = cf_string("PreventUserIdleSystemSleep")?;
+        let name = match cf_string(reason) {
+            Ok(name) => name,
+            Err(e) => {
+                unsafe { CFRelease(kind) };
+                return Err(e);
+            }
+        };
+        let mut id = 0;
+        let status =
+            unsafe { IOPMAssertionCreateWithName(kind, IOPM_ASSERTION_LEVEL_ON, name, &mut id) };
+        unsafe {
+            CFRelease(kind);
+            CFRelease(name);
+        }
+        if status != 0 {
+            return Err(MistError::Process(format!(
+                "IOPMAssertionCreateWithName failed ({status:#x})"
+            )));
+        }
+        Ok(Held { id })
+    }
+
+    fn cf_string(s: &str) -> Result<CFStringRef, MistError> {
+        let c = CString::new(s).map_err(|e| MistError::Validation(e.to_string()))?;
+        let cf = unsafe {
+            CFStringCreateWithCString(std::ptr::null(), c.as_ptr(), CF_STRING_ENCODING_UTF8)
+        };
+        if cf.is_null() {
+            return Err(MistError::Process("failed to create CFString".into()));
+        }
+        Ok(cf)
+    }
+
+    impl Drop for Held {
+        fn drop(&mut self) {
+            unsafe { IOPMAssertionRelease(self.id) };
+        }
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use std::process::{Child, Command, Stdio};
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::error::MistError;
+
+    pub struct Held {
+        child: Child,
+    }
+
+    pub fn acquire(reason: &str) -> Result<Held, MistError> {
+        let mut child = Command::new("systemd-inhibit")
+            .args(["--what=sleep:idle", "--who=MIST", "--mode=block"])
+            .arg(format!("--why={reason}"))
+            .args(["sleep", "infinity"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| MistError::Process(format!("failed to run systemd-inhibit: {e}")))?;
+        // systemd-inhibit exits straight away if logind refuses the lock.
+        thread::sleep(Duration::from_millis(100));
+        if let Ok(Some(status)) = child.try_wait() {
+            return Err(MistError::Process(format!(
+                "systemd-inhibit exited with {status}"
+            )));
+        }
+        Ok(Held { child })
+    }
+
+    impl Drop for Held {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
@@ -0,0 +1,200 @@
+// Pre-flight check for the Python interpreter used to run the core.
+//
+// With several Python installs side by side it is easy to point MIST at
+// one that lacks `mist_core`. `validate_python_environment` confirms that
+// the interpreter runs and can import the core before it is used. A
+// successful result is cached for a minute so settings screens can
+// re-validate freely.
+
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::error::MistError;
+
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PythonEnvInfo {
+    pub python_version: String,
+    pub core_version: String,
+    pub path: String,
+}
+
+/// Last successful validation and when it ran.
+#[derive(Default)]
+pub struct PythonEnvCache(Mutex<Option<(Instant, PythonEnvInfo)>>);
+
+/// Check that `python_path` is a runnable interpreter with `mist_core`
+/// importable, and report both versions.
+#[tauri::command]
+#[tracing::instrument(skip(cache))]
+pub async fn validate_python_environment(
+    cache: State<'_, PythonEnvCache>,
+    python_path: String,
+) -> Result<PythonEnvInfo, MistError> {
+    if let Some((at, info)) = cache.0.lock()?.as_ref() {
+        if info.path == python_path && at.elapsed() < CACHE_TTL {
+            return Ok(info.clone());
+        }
+    }
+
+    let path = python_path.clone();
+    let info = tauri::async_runtime::spawn_blocking(move || probe(&path)).await??;
+    *cache.0.lock()? = Some((Instant::now(), info.clone()));
+    Ok(info)
+}
+
+fn probe(python_path: &str) -> Result<PythonEnvInfo, MistError> {
+    check_executable(&resolve(Path::new(python_path))?)?;
+
+    let version = run(python_path, &["--version"])?;
+    let core = run(
+        python_path,
+        &["-c", "import mist_core; print(mist_core.__version__)"],
+    )?;
+
+    let mut stderr = String::new();
+    for output in [&version, &core] {
+        if !output.status.success() {
+            stderr.push_str(String::from_utf8_lossy(&output.stderr).trim());
+            stderr.push('\n');
+        }
+    }
+    if !stderr.is_empty() {
+        return Err(MistError::Process(stderr.trim_end().to_string()));
+    }
+
+    // Python 2 and early 3.x print the version to stderr.
+    let banner = if version.stdout.is_empty() {
+        &version.stderr
+    } else {
+        &version.stdout
+    };
+    let banner = String::from_utf8_lossy(banner);
+    Ok(PythonEnvInfo {
+        python_version: parse_version(&banner)
+            .ok_or_else(|| {
+                MistError::Process(format!("unexpected --version output: {}", banner.trim()))
+            })?
+            .to_string(),
+        core_version: String::from_utf8_lossy(&core.stdout).trim().to_string(),
+        path: python_path.to_string(),
+    })
+}
+
+fn run(python_path: &str, args: &[&str]) -> Result<Output, MistError> {
+    Command::new(python_path)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| MistError::Process(format!("failed to run {python_path}: {e}")))
+}
+
+/// A bare name such as `python3` is looked up on `PATH`, as the spawn
+/// would; anything with a directory part is used as given.
+fn resolve(path: &Path) -> Result<PathBuf, MistError> {
+    if path.components().count() != 1 {
+        return Ok(path.to_path_buf());
+    }
+    let search = std::env::var_os("PATH").unwrap_or_default();
+    find_on_path(path.as_os_str(), &search)
+        .ok_or_else(|| MistError::Validation(format!("{} was not found on PATH", path.display())))
+}
+
+fn find_on_path(name: &OsStr, search: &OsStr) -> Option<PathBuf> {
+    std::env::split_paths(search).find_map(|dir| {
+        let candidate = dir.join(name);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        if cfg!(windows) {
+            let mut exe = candidate.into_os_string();
+            exe.push(".exe");
+            let exe = PathBuf::from(exe);
+            if exe.is_file() {
+                return Some(exe);
+            }
+        }
+        None
+    })
+}
+
+/// Fail early, with a clearer message than the spawn error, if `path` is
+/// not a readable, executable file.
+fn check_executable(path: &Path) -> Result<(), MistError> {
+    let meta = fs::metadata(path)
+        .map_err(|e| MistError::Validation(format!("{}: {e}", path.display())))?;
+    if !meta.is_file() {
+        return Err(MistError::Validation(format!(
+            "{} is not a file",
+            path.display()
+        )));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = meta.permissions().mode();
+        if mode & 0o444 == 0 {
+            return Err(MistError::Validation(format!(
+                "{} is not readable",
+                path.display()
+            )));
+        }
+        if mode & 0o111 == 0 {
+            return Err(MistError::Validation(format!(
+                "{} is not executable",
+                path.display()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// `"Python 3.12.1"` -> `"3.12.1"`.
+fn parse_version(banner: &str) -> Option<&str> {
+    banner.trim().strip_prefix("Python ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_version_banner() {
+        assert_eq!(parse_version("Python 3.12.1\n"), Some("3.12.1"));
+        assert_eq!(parse_version("pypy"), None);
+    }
+
+    #[test]
+    fn finds_bare_name_on_path() {
+        let scratch = tempfile::tempdir().unwrap();
+        let dir = scratch.path();
+        fs::write(dir.join("python3"), "").unwrap();
+
+        let search = std::env::join_paths([Path::new("/nonexistent"), dir]).unwrap();
+        assert_eq!(
+            find_on_path(OsStr::new("python3"), &search),
+            Some(dir.join("python3"))
+        );
+        assert_eq!(find_on_path(OsStr::new("python9"), &search), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_non_executable_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+
+        let err = check_executable(file.path()).unwrap_err();
+        assert!(
+            matches!(&err, MistError::Validation(m) if m.ends_with("is not executable")),
+            "{err}"
+        );
+    }
+}
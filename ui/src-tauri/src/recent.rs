@@ -0,0 +1,118 @@
+// Recently opened files, most recent first.
+//
+// The list lives in `recent_files.json` in the app data directory and is
+// rewritten on every change. It is MIST's own list: Tauri exposes nothing
+// for the macOS Open Recent menu (`NSDocumentController`), so that menu is
+// left alone.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Runtime, State};
+
+use crate::error::MistError;
+
+const RECENT_FILE: &str = "recent_files.json";
+const MAX_RECENT: usize = 20;
+
+#[derive(Default)]
+pub struct RecentFiles(Mutex<VecDeque<String>>);
+
+/// Restore the list saved by a previous session.
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), MistError> {
+    let path = recent_path(app)?;
+    if !path.exists() {
+        return Ok(());
+    }
+    let raw = fs::read_to_string(&path)
+        .map_err(|e| MistError::Io(format!("failed to read {}: {e}", path.display())))?;
+    let mut files: VecDeque<String> = serde_json::from_str(&raw)
+        .map_err(|e| MistError::Config(format!("invalid {}: {e}", path.display())))?;
+    files.truncate(MAX_RECENT);
+    *app.state::<RecentFiles>().0.lock()? = files;
+    Ok(())
+}
+
+/// Move `path` to the front of the list, adding it if new.
+#[tauri::command]
+#[tracing::instrument(skip(app, recent))]
+pub fn add_recent_file<R: Runtime>(
+    app: AppHandle<R>,
+    recent: State<'_, RecentFiles>,
+    path: String,
+) -> Result<(), MistError> {
+    let mut files = recent.0.lock()?;
+    push_front(&mut files, path);
+    save(&app, &files)
+}
+
+/// The list, most recent first.
+#[tauri::command]
+#[tracing::instrument(skip(recent))]
+pub fn get_recent_files(recent: State<'_, RecentFiles>) -> Vec<String> {
+    let files = recent.0.lock().unwrap_or_else(|e| e.into_inner());
+    files.iter().cloned().collect()
+}
+
+/// Empty the list and delete its file.
+#[tauri::command]
+#[tracing::instrument(skip(app, recent))]
+pub fn clear_recent_files<R: Runtime>(
+    app: AppHandle<R>,
+    recent: State<'_, RecentFiles>,
+) -> Result<(), MistError> {
+    let mut files = recent.0.lock()?;
+    files.clear();
+    let path = recent_path(&app)?;
+    match fs::remove_file(&path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(MistError::Io(format!(
+            "failed to remove {}: {e}",
+            path.display()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn push_front(files: &mut VecDeque<String>, path: String) {
+    files.retain(|existing| *existing != path);
+    files.push_front(path);
+    files.truncate(MAX_RECENT);
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, files: &VecDeque<String>) -> Result<(), MistError> {
+    let path = recent_path(app)?;
+    let raw = serde_json::to_string_pretty(files).map_err(|e| MistError::Config(e.to_string()))?;
+    fs::write(&path, raw)
+        .map_err(|e| MistError::Io(format!("failed to write {}: {e}", path.display())))
+}
+
+fn recent_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, MistError> {
+    let dir = app.path().app_data_dir()?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| MistError::Io(format!("failed to create {}: {e}", dir.display())))?;
+    Ok(dir.join(RECENT_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_front_dedups_and_caps() {
+        let mut files = VecDeque::new();
+        for i in 0..25 {
+            push_front(&mut files, format!("/data/{i}.csv"));
+        }
+        assert_eq!(files.len(), MAX_RECENT);
+        assert_eq!(files[0], "/data/24.csv");
+        assert_eq!(files[MAX_RECENT - 1], "/data/5.csv");
+
+        push_front(&mut files, "/data/10.csv".into());
+        assert_eq!(files.len(), MAX_RECENT);
+        assert_eq!(files[0], "/data/10.csv");
+        assert_eq!(files.iter().filter(|f| *f == "/data/10.csv").count(), 1);
+    }
+}
@@ -0,0 +1,155 @@
+// Rendering hints for machines where the UI stutters, typically Windows
+// boxes that fall back to software rasterisation.
+//
+// Webviews only read these knobs when they are created, and
+// ICoreWebView2Settings has none of them, so they cannot be applied
+// through `with_webview`. Instead the main window is left out of
+// tauri.conf.json's startup windows (`create: false`) and created in
+// `setup` from the same config with the saved hints applied, before it is
+// ever shown. `set_rendering_hints` saves them as the `[rendering]` config
+// table and they take effect on the next start.
+//
+// - Windows: WebView2 browser arguments (`additional_browser_args`, Tauri
+//   2.0). `prefer_hardware` forces GPU rasterisation even for blocklisted
+//   drivers, `disable_gpu_compositing` composites on the CPU, and turning
+//   `background_throttling` off passes Chromium's background flags.
+// - macOS: only `background_throttling`, as WKWebView's inactive
+//   scheduling policy (`background_throttling`, Tauri 2.3, macOS 14).
+// - Linux: Tauri exposes none of these for WebKitGTK.
+
+use serde::{Deserialize, Serialize};
+use tauri::utils::config::{BackgroundThrottlingPolicy, WindowConfig};
+use tauri::{AppHandle, Runtime, WebviewWindowBuilder};
+
+use crate::{config, error::MistError, window};
+
+/// wry's own WebView2 arguments, which are dropped once any are given.
+const WRY_BROWSER_ARGS: &str = "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenderingHints {
+    /// Rasterise on the GPU even where the driver is blocklisted.
+    pub prefer_hardware: bool,
+    /// Composite on the CPU, for drivers that glitch when compositing.
+    pub disable_gpu_compositing: bool,
+    /// Let the webview slow down timers and painting while hidden.
+    pub background_throttling: bool,
+}
+
+impl Default for RenderingHints {
+    fn default() -> Self {
+        Self {
+            prefer_hardware: false,
+            disable_gpu_compositing: false,
+            background_throttling: true,
+        }
+    }
+}
+
+/// Save rendering hints for the main window, applied from the next start.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub fn set_rendering_hints<R: Runtime>(
+    app: AppHandle<R>,
+    hints: RenderingHints,
+) -> Result<(), MistError> {
+    if cfg!(target_os = "linux") {
+        return Err(MistError::Process("platform not supported".into()));
+    }
+    config::update(&app, |doc| {
+        let section = doc["rendering"].or_insert(toml_edit::table());
+        section["prefer_hardware"] = toml_edit::value(hints.prefer_hardware);
+        section["disable_gpu_compositing"] = toml_edit::value(hints.disable_gpu_compositing);
+        section["background_throttling"] = toml_edit::value(hints.background_throttling);
+    })
+}
+
+/// Create the main window from tauri.conf.json with the saved hints.
+pub fn create_main_window<R: Runtime>(app: &AppHandle<R>) -> Result<(), MistError> {
+    let mut config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|config| config.label == window::MAIN)
+        .cloned()
+        .ok_or_else(|| MistError::Config("no main window in tauri.conf.json".into()))?;
+    let hints = saved_hints(app).unwrap_or_else(|e| {
+        tracing::warn!("ignoring rendering hints: {e}");
+        RenderingHints::default()
+    });
+    apply(&mut config, hints);
+    WebviewWindowBuilder::from_config(app, &config)?.build()?;
+    Ok(())
+}
+
+fn saved_hints<R: Runtime>(app: &AppHandle<R>) -> Result<RenderingHints, MistError> {
+    let config = config::load(app)?;
+    let Some(section) = config.get("rendering") else {
+        return Ok(RenderingHints::default());
+    };
+    section
+        .clone()
+        .try_into()
+        .map_err(|e| MistError::Config(format!("invalid [rendering] config: {e}")))
+}
+
+fn apply(config: &mut WindowConfig, hints: RenderingHints) {
+    let mut args = Vec::new();
+    if hints.prefer_hardware {
+        args.extend(["--enable-gpu-rasterization", "--ignore-gpu-blocklist"]);
+    }
+    if hints.disable_gpu_compositing {
+        args.push("--disable-gpu-compositing");
+    }
+    if !hints.background_throttling {
+        args.extend([
+            "--disable-background-timer-throttling",
+            "--disable-backgrounding-occluded-windows",
+            "--disable-renderer-backgrounding",
+        ]);
+        config.background_throttling = Some(BackgroundThrottlingPolicy::Disabled);
+    }
+    if !args.is_empty() {
+        args.insert(0, WRY_BROWSER_ARGS);
+        config.additional_browser_args = Some(args.join(" "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_hints_leave_window_untouched() {
+        let mut config = WindowConfig::default();
+        apply(&mut config, RenderingHints::default());
+        assert_eq!(config, WindowConfig::default());
+    }
+
+    #[test]
+    fn hints_become_browser_args() {
+        let mut config = WindowConfig::default();
+        apply(
+            &mut config,
+            RenderingHints {
+                prefer_hardware: false,
+                disable_gpu_compositing: true,
+                background_throttling: false,
+            },
+        );
+        assert_eq!(
+            config.additional_browser_args.as_deref(),
+            Some(
+                "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection \
+                 --disable-gpu-compositing --disable-background-timer-throttling \
+                 --disable-backgrounding-occluded-windows --disable-renderer-backgrounding"
+            )
+        );
+        assert_eq!(
+            config.background_throttling,
+            Some(BackgroundThrottlingPolicy::Disabled)
+        );
+    }
+}
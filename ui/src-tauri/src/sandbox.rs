@@ -0,0 +1,449 @@
+// Optional privilege drop for the Python core, set in the `[sandbox]`
+// config table.
+//
+// With `run_as_restricted_user` the core is spawned as `nobody` on Linux
+// and macOS, which needs the app itself to run as root; without root the
+// core starts as the current user and `sandbox://warning` says why. On
+// Windows the core is started suspended and given a restricted copy of
+// the app's token, with administrator rights and all privileges but
+// SeChangeNotify removed, before it runs. `allowed_paths` is handed to the
+// core as `MIST_ALLOWED_PATHS`, joined like `PATH`, for it to enforce.
+
+use std::process::{Child, Command};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::{config, error::MistError};
+
+const ALLOWED_PATHS_VAR: &str = "MIST_ALLOWED_PATHS";
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SandboxConfig {
+    pub run_as_restricted_user: bool,
+    pub allowed_paths: Vec<String>,
+}
+
+/// Who the core was last started as, with the ids read back from the
+/// running process. The ids are `None` on Windows, before the first start
+/// and when the core exited before they could be read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct SandboxStatus {
+    pub restricted: bool,
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+}
+
+#[derive(Default)]
+pub struct SandboxState(Mutex<SandboxStatus>);
+
+/// Effective user and group of the core as last started.
+#[tauri::command]
+#[tracing::instrument(skip(state))]
+pub fn get_sandbox_status(state: State<'_, SandboxState>) -> SandboxStatus {
+    *state.0.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Apply the sandbox config to the core's `command`. Returns whether the
+/// core should be restricted, to pass to `started` once it has spawned.
+pub fn prepare<R: Runtime>(app: &AppHandle<R>, command: &mut Command) -> bool {
+    let sandbox = load(app).unwrap_or_else(|e| {
+        tracing::warn!("ignoring sandbox config: {e}");
+        SandboxConfig::default()
+    });
+    if !sandbox.allowed_paths.is_empty() {
+        match std::env::join_paths(&sandbox.allowed_paths) {
+            Ok(paths) => {
+                command.env(ALLOWED_PATHS_VAR, paths);
+            }
+            Err(e) => warn(app, &format!("cannot pass allowed_paths to the core: {e}")),
+        }
+    }
+    if !sandbox.run_as_restricted_user {
+        return false;
+    }
+    match platform::restrict(command) {
+        Ok(()) => true,
+        Err(reason) => {
+            warn(app, &format!("core not sandboxed: {reason}"));
+            false
+        }
+    }
+}
+
+/// Finish sandboxing a core that spawned successfully and remember who it
+/// runs as. On Windows this also resumes the suspended core, so it must be
+/// called for every core `prepare` restricted.
+pub fn started<R: Runtime>(app: &AppHandle<R>, restricted: bool, child: &Child) {
+    let restricted = restricted
+        && match platform::started(child) {
+            Ok(()) => true,
+            Err(reason) => {
+                warn(app, &format!("core not sandboxed: {reason}"));
+                false
+            }
+        };
+    let (uid, gid) = platform::ids(child.id());
+    let status = SandboxStatus {
+        restricted,
+        uid,
+        gid,
+    };
+    *app.state::<SandboxState>()
+        .0
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = status;
+}
+
+fn load<R: Runtime>(app: &AppHandle<R>) -> Result<SandboxConfig, MistError> {
+    let config = config::load(app)?;
+    let Some(section) = config.get("sandbox") else {
+        return Ok(SandboxConfig::default());
+    };
+    section
+        .clone()
+        .try_into()
+        .map_err(|e| MistError::Config(format!("invalid [sandbox] config: {e}")))
+}
+
+fn warn<R: Runtime>(app: &AppHandle<R>, message: &str) {
+    tracing::warn!("{message}");
+    let _ = app.emit("sandbox://warning", message);
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::os::unix::process::CommandExt;
+    use std::process::{Child, Command};
+
+    const RESTRICTED_USER: &std::ffi::CStr = c"nobody";
+
+    /// The child drops its supplementary groups and switches to `nobody`
+    /// before exec.
+    pub fn restrict(command: &mut Command) -> Result<(), String> {
+        let (uid, gid) = restricted_ids()?;
+        command.uid(uid).gid(gid);
+        Ok(())
+    }
+
+    pub fn started(_child: &Child) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn restricted_ids() -> Result<(u32, u32), String> {
+        // SAFETY: geteuid has no preconditions.
+        if unsafe { libc::geteuid() } != 0 {
+            return Err("switching users needs root".into());
+        }
+        // SAFETY: an all-zero passwd is a valid out-parameter.
+        let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+        let mut buf = vec![0; 4096];
+        let mut found = std::ptr::null_mut();
+        // SAFETY: every pointer is valid for the call and `buf` is
+        // `buf.len()` bytes long. Only the ids are read afterwards.
+        let rc = unsafe {
+            libc::getpwnam_r(
+                RESTRICTED_USER.as_ptr(),
+                &mut passwd,
+                buf.as_mut_ptr(),
+                buf.len(),
+                &mut found,
+            )
+        };
+        if rc != 0 || found.is_null() {
+            return Err("user nobody not found".into());
+        }
+        Ok((passwd.pw_uid, passwd.pw_gid))
+    }
+
+    /// Effective user and group of process `pid`.
+    #[cfg(target_os = "linux")]
+    pub fn ids(pid: u32) -> (Option<u32>, Option<u32>) {
+        std::fs::read_to_string(format!("/proc/{pid}/status"))
+            .map(|status| effective_ids(&status))
+            .unwrap_or_default()
+    }
+
+    /// The effective ids are the second of the four on the `Uid:` and
+    /// `Gid:` lines of `/proc/<pid>/status`.
+    #[cfg(target_os = "linux")]
+    pub fn effective_ids(status: &str) -> (Option<u32>, Option<u32>) {
+        let field = |name: &str| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix(name))
+                .and_then(|ids| ids.split_whitespace().nth(1))
+                .and_then(|id| id.parse().ok())
+        };
+        (field("Uid:"), field("Gid:"))
+    }
+
+    /// Effective user and group of process `pid`.
+    #[cfg(target_os = "macos")]
+    pub fn ids(pid: u32) -> (Option<u32>, Option<u32>) {
+        let Ok(pid) = libc::c_int::try_from(pid) else {
+            return (None, None);
+        };
+        // SAFETY: an all-zero proc_bsdinfo is a valid out-parameter.
+        let mut info: libc::proc_bsdinfo = unsafe { std::mem::zeroed() };
+        let size = std::mem::size_of::<libc::proc_bsdinfo>() as libc::c_int;
+        // SAFETY: `info` is `size` bytes long and lives for the call.
+        let written = unsafe {
+            libc::proc_pidinfo(
+                pid,
+                libc::PROC_PIDTBSDINFO,
+                0,
+                std::ptr::from_mut(&mut info).cast(),
+                size,
+            )
+        };
+        if written != size {
+            return (None, None);
+        }
+        (Some(info.pbi_uid), Some(info.pbi_gid))
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    pub fn ids(_pid: u32) -> (Option<u32>, Option<u32>) {
+        (None, None)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::ffi::c_void;
+    use std::os::windows::io::AsRawHandle;
+    use std::os::windows::process::CommandExt;
+    use std::process::{Child, Command};
+
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Security::{
+        CreateRestrictedToken, CreateWellKnownSid, WinBuiltinAdministratorsSid,
+        DISABLE_MAX_PRIVILEGE, SECURITY_MAX_SID_SIZE, SID_AND_ATTRIBUTES, TOKEN_ASSIGN_PRIMARY,
+        TOKEN_DUPLICATE, TOKEN_QUERY,
+    };
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32,
+    };
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentProcess, OpenProcessToken, OpenThread, ResumeThread, CREATE_SUSPENDED,
+        THREAD_SUSPEND_RESUME,
+    };
+
+    /// `ProcessAccessToken` from `PROCESSINFOCLASS`.
+    const PROCESS_ACCESS_TOKEN: i32 = 9;
+
+    #[repr(C)]
+    struct ProcessAccessToken {
+        token: HANDLE,
+        thread: HANDLE,
+    }
+
+    #[link(name = "ntdll")]
+    extern "system" {
+        fn NtSetInformationProcess(
+            process: HANDLE,
+            class: i32,
+            information: *const c_void,
+            length: u32,
+        ) -> i32;
+    }
+
+    /// Closes the handle when dropped.
+    struct Handle(HANDLE);
+
+    impl Drop for Handle {
+        fn drop(&mut self) {
+            // SAFETY: the handle is open and owned by this guard.
+            unsafe { CloseHandle(self.0) };
+        }
+    }
+
+    /// The core is started suspended so `started` can swap its token
+    /// before any of its code runs.
+    pub fn restrict(command: &mut Command) -> Result<(), String> {
+        command.creation_flags(CREATE_SUSPENDED);
+        Ok(())
+    }
+
+    /// Give the suspended core a restricted token, then resume it whether
+    /// or not that worked.
+    pub fn started(child: &Child) -> Result<(), String> {
+        let restricted = assign_restricted_token(child);
+        resume(child.id())?;
+        restricted
+    }
+
+    fn assign_restricted_token(child: &Child) -> Result<(), String> {
+        let mut token = std::ptr::null_mut();
+        // SAFETY: the pseudo handle from GetCurrentProcess needs no
+        // closing and `token` is a valid out-parameter.
+        if unsafe {
+            OpenProcessToken(
+                GetCurrentProcess(),
+                TOKEN_DUPLICATE | TOKEN_ASSIGN_PRIMARY | TOKEN_QUERY,
+                &mut token,
+            )
+        } == 0
+        {
+            return Err(format!("cannot open app token: {}", last_error()));
+        }
+        let token = Handle(token);
+
+        let mut sid = [0u8; SECURITY_MAX_SID_SIZE as usize];
+        let mut sid_size = SECURITY_MAX_SID_SIZE;
+        // SAFETY: `sid` is `sid_size` bytes long.
+        if unsafe {
+            CreateWellKnownSid(
+                WinBuiltinAdministratorsSid,
+                std::ptr::null_mut(),
+                sid.as_mut_ptr().cast(),
+                &mut sid_size,
+            )
+        } == 0
+        {
+            return Err(format!("cannot build administrators SID: {}", last_error()));
+        }
+        let disable = SID_AND_ATTRIBUTES {
+            Sid: sid.as_mut_ptr().cast(),
+            Attributes: 0,
+        };
+        let mut restricted = std::ptr::null_mut();
+        // SAFETY: `token` is open with TOKEN_DUPLICATE, `disable` points
+        // into `sid`, which outlives the call, and `restricted` is a valid
+        // out-parameter.
+        if unsafe {
+            CreateRestrictedToken(
+                token.0,
+                DISABLE_MAX_PRIVILEGE,
+                1,
+                &disable,
+                0,
+                std::ptr::null(),
+                0,
+                std::ptr::null(),
+                &mut restricted,
+            )
+        } == 0
+        {
+            return Err(format!("cannot restrict app token: {}", last_error()));
+        }
+        let restricted = Handle(restricted);
+
+        let information = ProcessAccessToken {
+            token: restricted.0,
+            thread: std::ptr::null_mut(),
+        };
+        // SAFETY: the child handle stays open while `child` is borrowed,
+        // and `information` is a valid PROCESS_ACCESS_TOKEN for the call.
+        let status = unsafe {
+            NtSetInformationProcess(
+                child.as_raw_handle(),
+                PROCESS_ACCESS_TOKEN,
+                std::ptr::from_ref(&information).cast(),
+                std::mem::size_of::<ProcessAccessToken>() as u32,
+            )
+        };
+        if status < 0 {
+            return Err(format!(
+                "cannot assign restricted token: NTSTATUS {status:#x}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Resume every thread of process `pid`; a freshly created suspended
+    /// process has just its main thread.
+    fn resume(pid: u32) -> Result<(), String> {
+        // SAFETY: CreateToolhelp32Snapshot has no pointer arguments.
+        let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0) };
+        if snapshot == INVALID_HANDLE_VALUE {
+            return Err(format!("cannot list core threads: {}", last_error()));
+        }
+        let snapshot = Handle(snapshot);
+        let mut entry = THREADENTRY32 {
+            dwSize: std::mem::size_of::<THREADENTRY32>() as u32,
+            ..Default::default()
+        };
+        let mut resumed = false;
+        // SAFETY: `snapshot` is open and `entry` has its size set.
+        let mut more = unsafe { Thread32First(snapshot.0, &mut entry) } != 0;
+        while more {
+            if entry.th32OwnerProcessID == pid {
+                // SAFETY: OpenThread has no pointer arguments.
+                let thread = unsafe { OpenThread(THREAD_SUSPEND_RESUME, 0, entry.th32ThreadID) };
+                if !thread.is_null() {
+                    let thread = Handle(thread);
+                    // SAFETY: `thread` is open with THREAD_SUSPEND_RESUME.
+                    resumed |= unsafe { ResumeThread(thread.0) } != u32::MAX;
+                }
+            }
+            // SAFETY: as for Thread32First.
+            more = unsafe { Thread32Next(snapshot.0, &mut entry) } != 0;
+        }
+        if resumed {
+            Ok(())
+        } else {
+            Err(format!("cannot resume core: {}", last_error()))
+        }
+    }
+
+    fn last_error() -> std::io::Error {
+        std::io::Error::last_os_error()
+    }
+
+    pub fn ids(_pid: u32) -> (Option<u32>, Option<u32>) {
+        (None, None)
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use std::process::{Child, Command};
+
+    pub fn restrict(_command: &mut Command) -> Result<(), String> {
+        Err("restricted users are not supported on this platform".into())
+    }
+
+    pub fn started(_child: &Child) -> Result<(), String> {
+        Ok(())
+    }
+
+    pub fn ids(_pid: u32) -> (Option<u32>, Option<u32>) {
+        (None, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sandbox_config() {
+        let table: toml::Table = toml::from_str(
+            "[sandbox]\nrun_as_restricted_user = true\nallowed_paths = [\"/data\"]\n",
+        )
+        .unwrap();
+        let sandbox: SandboxConfig = table["sandbox"].clone().try_into().unwrap();
+        assert_eq!(
+            sandbox,
+            SandboxConfig {
+                run_as_restricted_user: true,
+                allowed_paths: vec!["/data".into()],
+            }
+        );
+
+        let empty: SandboxConfig = toml::Value::Table(toml::Table::new()).try_into().unwrap();
+        assert_eq!(empty, SandboxConfig::default());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reads_effective_ids_from_proc_status() {
+        let status = "Name:\tpython3\nUid:\t0\t65534\t65534\t65534\nGid:\t0\t65533\t65533\t65533\n";
+        assert_eq!(platform::effective_ids(status), (Some(65534), Some(65533)));
+        assert_eq!(platform::effective_ids("Name:\tpython3\n"), (None, None));
+    }
+}
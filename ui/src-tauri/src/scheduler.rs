@@ -0,0 +1,205 @@
+// Recurring events on a cron schedule, for in-app automation such as
+// hourly auto-saves.
+//
+// Each task is a tokio task that sleeps until the next tick and then
+// emits its event with the task id as payload. Expressions use the
+// `cron` crate's syntax, which has a leading seconds field:
+// `sec min hour day-of-month month day-of-week [year]`. Ticks are
+// computed from the wall clock after every wake-up, so ticks missed
+// while the machine slept are skipped rather than replayed.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+use tokio_util::sync::CancellationToken;
+
+use crate::{error::MistError, shortcuts};
+
+const MAX_TASKS: usize = 20;
+
+/// Scheduled tasks, keyed by id.
+#[derive(Default)]
+pub struct ScheduledTasks(Mutex<HashMap<String, Task>>);
+
+struct Task {
+    cron_expr: String,
+    event_name: String,
+    schedule: Schedule,
+    cancel: CancellationToken,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskInfo {
+    pub id: String,
+    pub cron_expr: String,
+    pub event_name: String,
+    pub next_run_utc: Option<DateTime<Utc>>,
+}
+
+/// Emit `event_name` on every tick of `cron_expr` until cancelled.
+/// Returns the id `cancel_task` takes.
+#[tauri::command]
+#[tracing::instrument(skip(app, tasks))]
+pub fn schedule_task<R: Runtime>(
+    app: AppHandle<R>,
+    tasks: State<'_, ScheduledTasks>,
+    cron_expr: String,
+    event_name: String,
+) -> Result<String, MistError> {
+    shortcuts::check_event_name(&event_name)?;
+    let schedule = parse(&cron_expr)?;
+    if next_run(&schedule, Utc::now()).is_none() {
+        return Err(MistError::Validation(format!(
+            "cron expression {cron_expr:?} has no future runs"
+        )));
+    }
+    let mut active = tasks.0.lock()?;
+    if active.len() >= MAX_TASKS {
+        return Err(MistError::Validation("task limit reached".into()));
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    let cancel = CancellationToken::new();
+    tauri::async_runtime::spawn(run(
+        app,
+        id.clone(),
+        schedule.clone(),
+        event_name.clone(),
+        cancel.clone(),
+    ));
+    active.insert(
+        id.clone(),
+        Task {
+            cron_expr,
+            event_name,
+            schedule,
+            cancel,
+        },
+    );
+    Ok(id)
+}
+
+/// Stop the task `id`.
+#[tauri::command]
+#[tracing::instrument(skip(tasks))]
+pub fn cancel_task(tasks: State<'_, ScheduledTasks>, id: String) -> Result<(), MistError> {
+    let task = tasks
+        .0
+        .lock()?
+        .remove(&id)
+        .ok_or_else(|| MistError::Validation(format!("no scheduled task {id:?}")))?;
+    task.cancel.cancel();
+    Ok(())
+}
+
+/// All scheduled tasks, soonest first.
+#[tauri::command]
+#[tracing::instrument(skip(tasks))]
+pub fn list_tasks(tasks: State<'_, ScheduledTasks>) -> Vec<TaskInfo> {
+    let tasks = tasks.0.lock().unwrap_or_else(|e| e.into_inner());
+    let now = Utc::now();
+    let mut infos: Vec<_> = tasks
+        .iter()
+        .map(|(id, task)| TaskInfo {
+            id: id.clone(),
+            cron_expr: task.cron_expr.clone(),
+            event_name: task.event_name.clone(),
+            next_run_utc: next_run(&task.schedule, now),
+        })
+        .collect();
+    // Tasks with no ticks left sort last.
+    infos.sort_by_key(|info| (info.next_run_utc.is_none(), info.next_run_utc));
+    infos
+}
+
+fn parse(cron_expr: &str) -> Result<Schedule, MistError> {
+    Schedule::from_str(cron_expr)
+        .map_err(|e| MistError::Validation(format!("invalid cron expression {cron_expr:?}: {e}")))
+}
+
+fn next_run(schedule: &Schedule, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    schedule.after(&after).next()
+}
+
+async fn run<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+    schedule: Schedule,
+    event_name: String,
+    cancel: CancellationToken,
+) {
+    // Never fire the same tick twice, even if the wall clock lags the
+    // timer on wake-up.
+    let mut last = Utc::now();
+    while let Some(next) = next_run(&schedule, Utc::now().max(last)) {
+        let delay = (next - Utc::now()).to_std().unwrap_or_default();
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {
+                let _ = app.emit(&event_name, &id);
+                last = next;
+            }
+            _ = cancel.cancelled() => return,
+        }
+    }
+    tracing::info!(%id, "scheduled task has no more ticks");
+    app.state::<ScheduledTasks>()
+        .0
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    #[test]
+    fn rejects_invalid_expression() {
+        assert!(matches!(parse("every hour"), Err(MistError::Validation(_))));
+    }
+
+    #[test]
+    fn next_run_follows_schedule() {
+        let hourly = parse("0 0 * * * *").unwrap();
+        let after = Utc.with_ymd_and_hms(2026, 3, 1, 10, 30, 0).unwrap();
+        assert_eq!(
+            next_run(&hourly, after),
+            Some(Utc.with_ymd_and_hms(2026, 3, 1, 11, 0, 0).unwrap())
+        );
+
+        let past = parse("0 0 0 1 1 * 2020").unwrap();
+        assert_eq!(next_run(&past, after), None);
+    }
+
+    #[test]
+    fn limits_task_count() {
+        let app = tauri::test::mock_app();
+        app.manage(ScheduledTasks::default());
+        let schedule = |app: &tauri::App<tauri::test::MockRuntime>| {
+            schedule_task(
+                app.handle().clone(),
+                app.state(),
+                "0 0 * * * *".into(),
+                "autosave".into(),
+            )
+        };
+        for _ in 0..MAX_TASKS {
+            schedule(&app).unwrap();
+        }
+        assert_eq!(
+            schedule(&app).unwrap_err(),
+            MistError::Validation("task limit reached".into())
+        );
+
+        let id = list_tasks(app.state()).remove(0).id;
+        cancel_task(app.state(), id).unwrap();
+        assert_eq!(list_tasks(app.state()).len(), MAX_TASKS - 1);
+        schedule(&app).unwrap();
+    }
+}
@@ -0,0 +1,314 @@
+// Screenshots of the main window, for attaching to bug reports.
+//
+// Tauri has no capture API, so each platform asks its webview for a PNG
+// snapshot through `with_webview`: WebView2 `CapturePreview` on Windows,
+// WKWebView `takeSnapshot` on macOS and `webkit_web_view_get_snapshot` on
+// Linux. Other platforms get an error. The snapshot is then re-encoded
+// as PNG, or as JPEG at quality 85.
+
+use std::io::Cursor;
+use std::time::Duration;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use image::codecs::jpeg::JpegEncoder;
+use image::ImageFormat;
+use serde::{Serialize, Serializer};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::oneshot;
+
+use crate::{error::MistError, window};
+
+const JPEG_QUALITY: u8 = 85;
+
+/// How long the webview has to deliver a snapshot.
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where a platform capture delivers its PNG, or why it failed.
+type Done = oneshot::Sender<Result<Vec<u8>, String>>;
+
+/// Encoded image bytes, sent to the frontend as a base64 string.
+pub struct Base64(Vec<u8>);
+
+impl Serialize for Base64 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(&self.0))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Png,
+    Jpeg,
+}
+
+impl Format {
+    fn parse(format: &str) -> Result<Self, MistError> {
+        match format {
+            "png" => Ok(Self::Png),
+            "jpeg" => Ok(Self::Jpeg),
+            other => Err(MistError::Validation(format!(
+                "unsupported image format {other:?}"
+            ))),
+        }
+    }
+}
+
+/// Capture the main window as `"png"` or `"jpeg"`.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub async fn window_screenshot<R: Runtime>(
+    app: AppHandle<R>,
+    format: String,
+) -> Result<Base64, MistError> {
+    let format = Format::parse(&format)?;
+    Ok(Base64(screenshot(&app, format).await?))
+}
+
+/// Capture the main window as `"png"` or `"jpeg"` and write it to `path`.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub async fn save_screenshot<R: Runtime>(
+    app: AppHandle<R>,
+    format: String,
+    path: String,
+) -> Result<(), MistError> {
+    let format = Format::parse(&format)?;
+    let bytes = screenshot(&app, format).await?;
+    tokio::fs::write(&path, bytes)
+        .await
+        .map_err(|e| MistError::Io(format!("failed to write {path}: {e}")))
+}
+
+async fn screenshot<R: Runtime>(app: &AppHandle<R>, format: Format) -> Result<Vec<u8>, MistError> {
+    let png = capture(app).await?;
+    tauri::async_runtime::spawn_blocking(move || encode(&png, format)).await?
+}
+
+async fn capture<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<u8>, MistError> {
+    let webview = app
+        .get_webview_window(window::MAIN)
+        .ok_or_else(|| MistError::Process("main window not found".into()))?;
+    let (done, snapshot) = oneshot::channel();
+    webview.with_webview(move |webview| platform::capture(webview, done))?;
+    match tokio::time::timeout(CAPTURE_TIMEOUT, snapshot).await {
+        Ok(Ok(png)) => png.map_err(|e| MistError::Process(format!("snapshot failed: {e}"))),
+        Ok(Err(_)) => Err(MistError::Process(
+            "webview dropped the snapshot request".into(),
+        )),
+        Err(_) => Err(MistError::Timeout),
+    }
+}
+
+fn encode(png: &[u8], format: Format) -> Result<Vec<u8>, MistError> {
+    let image = image::load_from_memory_with_format(png, ImageFormat::Png)
+        .map_err(|e| MistError::Process(format!("unreadable snapshot: {e}")))?;
+    let mut out = Vec::new();
+    match format {
+        Format::Png => image.write_to(&mut Cursor::new(&mut out), ImageFormat::Png),
+        // JPEG has no alpha channel.
+        Format::Jpeg => {
+            JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY).encode_image(&image.to_rgb8())
+        }
+    }
+    .map_err(|e| MistError::Io(format!("failed to encode screenshot: {e}")))?;
+    Ok(out)
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use tauri::webview::PlatformWebview;
+    use webkit2gtk::{gio, SnapshotOptions, SnapshotRegion, WebViewExt};
+
+    use super::Done;
+
+    pub fn capture(webview: PlatformWebview, done: Done) {
+        webview.inner().snapshot(
+            SnapshotRegion::Visible,
+            SnapshotOptions::NONE,
+            None::<&gio::Cancellable>,
+            move |surface| {
+                let png = surface.map_err(|e| e.to_string()).and_then(|surface| {
+                    let mut png = Vec::new();
+                    surface.write_to_png(&mut png).map_err(|e| e.to_string())?;
+                    Ok(png)
+                });
+                let _ = done.send(png);
+            },
+        );
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use tauri::webview::PlatformWebview;
+    use webview2_com::CapturePreviewCompletedHandler;
+    use webview2_com::Microsoft::Web::WebView2::Win32::COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG;
+    use windows::core::{Error, Result};
+    use windows::Win32::Foundation::E_OUTOFMEMORY;
+    use windows::Win32::System::Com::{IStream, STREAM_SEEK_SET};
+    use windows::Win32::UI::Shell::SHCreateMemStream;
+
+    use super::Done;
+
+    pub fn capture(webview: PlatformWebview, done: Done) {
+        let done = Rc::new(Cell::new(Some(done)));
+        if let Err(e) = start(&webview, done.clone()) {
+            if let Some(done) = done.take() {
+                let _ = done.send(Err(e.to_string()));
+            }
+        }
+    }
+
+    fn start(webview: &PlatformWebview, done: Rc<Cell<Option<Done>>>) -> Result<()> {
+        // SAFETY: `with_webview` runs this on the thread that owns the
+        // controller, and the stream outlives the capture via the handler.
+        unsafe {
+            let core = webview.controller().CoreWebView2()?;
+            let stream = SHCreateMemStream(None).ok_or_else(|| Error::from(E_OUTOFMEMORY))?;
+            let handler = CapturePreviewCompletedHandler::create(Box::new({
+                let stream = stream.clone();
+                move |result: Result<()>| {
+                    if let Some(done) = done.take() {
+                        let png = result.and_then(|()| read_all(&stream));
+                        let _ = done.send(png.map_err(|e| e.to_string()));
+                    }
+                    Ok(())
+                }
+            }));
+            core.CapturePreview(
+                COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG,
+                &stream,
+                &handler,
+            )
+        }
+    }
+
+    fn read_all(stream: &IStream) -> Result<Vec<u8>> {
+        let mut png = Vec::new();
+        let mut buf = vec![0u8; 64 * 1024];
+        // SAFETY: `buf` is valid for `buf.len()` bytes and `read` reports
+        // how many of them were written.
+        unsafe {
+            stream.Seek(0, STREAM_SEEK_SET, None)?;
+            loop {
+                let mut read = 0u32;
+                stream
+                    .Read(
+                        buf.as_mut_ptr().cast(),
+                        buf.len() as u32,
+                        Some(&mut read as *mut u32),
+                    )
+                    .ok()?;
+                if read == 0 {
+                    return Ok(png);
+                }
+                png.extend_from_slice(&buf[..read as usize]);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::cell::Cell;
+
+    use block2::RcBlock;
+    use objc2_app_kit::{NSBitmapImageFileType, NSBitmapImageRep, NSImage};
+    use objc2_foundation::{NSDictionary, NSError};
+    use objc2_web_kit::WKWebView;
+    use tauri::webview::PlatformWebview;
+
+    use super::Done;
+
+    pub fn capture(webview: PlatformWebview, done: Done) {
+        let done = Cell::new(Some(done));
+        let handler = RcBlock::new(move |image: *mut NSImage, error: *mut NSError| {
+            let Some(done) = done.take() else {
+                return;
+            };
+            // SAFETY: WebKit passes either a valid image or a valid error.
+            let png = match unsafe { (image.as_ref(), error.as_ref()) } {
+                (Some(image), _) => png_data(image),
+                (None, Some(error)) => Err(error.localizedDescription().to_string()),
+                (None, None) => Err("no image returned".to_string()),
+            };
+            let _ = done.send(png);
+        });
+        // SAFETY: on macOS `inner()` is the WKWebView, and `with_webview`
+        // runs this on the main thread.
+        unsafe {
+            let webview: &WKWebView = &*webview.inner().cast();
+            webview.takeSnapshotWithConfiguration_completionHandler(None, &handler);
+        }
+    }
+
+    fn png_data(image: &NSImage) -> Result<Vec<u8>, String> {
+        let tiff = image.TIFFRepresentation().ok_or("image has no bitmap")?;
+        let bitmap = NSBitmapImageRep::imageRepWithData(&tiff).ok_or("image has no bitmap")?;
+        // SAFETY: an empty property dictionary is always valid.
+        let png = unsafe {
+            bitmap.representationUsingType_properties(
+                NSBitmapImageFileType::PNG,
+                &NSDictionary::new(),
+            )
+        }
+        .ok_or("failed to encode image as PNG")?;
+        Ok(png.to_vec())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use tauri::webview::PlatformWebview;
+
+    use super::Done;
+
+    pub fn capture(_webview: PlatformWebview, done: Done) {
+        let _ = done.send(Err("not supported on this platform".into()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use image::{Rgba, RgbaImage};
+
+    fn sample_png() -> Vec<u8> {
+        let mut png = Vec::new();
+        RgbaImage::from_pixel(4, 3, Rgba([200, 40, 40, 255]))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        png
+    }
+
+    #[test]
+    fn rejects_unknown_format() {
+        assert!(matches!(
+            Format::parse("gif"),
+            Err(MistError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn encodes_png_and_jpeg() {
+        let png = encode(&sample_png(), Format::Png).unwrap();
+        assert_eq!(image::guess_format(&png).unwrap(), ImageFormat::Png);
+        let jpeg = encode(&sample_png(), Format::Jpeg).unwrap();
+        assert_eq!(image::guess_format(&jpeg).unwrap(), ImageFormat::Jpeg);
+        let decoded = image::load_from_memory(&jpeg).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (4, 3));
+    }
+
+    #[test]
+    fn serialises_as_base64() {
+        assert_eq!(
+            serde_json::to_value(Base64(b"mist".to_vec())).unwrap(),
+            "bWlzdA=="
+        );
+    }
+}
@@ -0,0 +1,499 @@
+// User-configurable global hotkeys.
+//
+// Each shortcut maps an accelerator string (e.g. `Ctrl+Shift+M`) to the
+// name of an event that is emitted to the frontend when it is pressed.
+// Registrations are persisted to the `[shortcuts]` config table and
+// restored at startup.
+//
+// A few built-in events are handled here as well as emitted: showing or
+// hiding the main window, restarting the core and, in debug builds,
+// toggling the DevTools. Their default accelerators are registered at
+// startup unless a saved shortcut already uses them. They are system-wide
+// like the rest, so they use `CmdOrCtrl+Alt+Shift` to stay clear of other
+// apps. Binding any accelerator to a built-in event moves it there;
+// removing a default one saves it with an empty event name so it stays
+// unbound.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::{config, core_process, error::MistError, window};
+
+/// Registered shortcuts, keyed by canonical accelerator (see `key`) so
+/// that `ctrl+shift+k` and `Shift+Control+K` are the same binding.
+#[derive(Default)]
+pub struct Shortcuts(Mutex<HashMap<String, Binding>>);
+
+#[derive(Clone)]
+struct Binding {
+    /// The accelerator as it was spelled when registered.
+    accelerator: String,
+    event_name: String,
+}
+
+#[derive(Serialize)]
+pub struct ShortcutEntry {
+    pub accelerator: String,
+    pub event_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ShortcutDescription {
+    pub accelerator: String,
+    pub description: String,
+    pub category: String,
+}
+
+struct BuiltinShortcut {
+    accelerator: &'static str,
+    event_name: &'static str,
+    action: Action,
+    description: &'static str,
+    category: &'static str,
+}
+
+#[derive(Clone, Copy)]
+enum Action {
+    ToggleWindow,
+    RestartCore,
+    #[cfg(debug_assertions)]
+    ToggleDevtools,
+}
+
+impl Action {
+    fn run<R: Runtime>(self, app: &AppHandle<R>) {
+        match self {
+            Action::ToggleWindow => window::toggle_main(app),
+            Action::RestartCore => core_process::spawn_restart(app),
+            #[cfg(debug_assertions)]
+            Action::ToggleDevtools => crate::devtools::toggle(app),
+        }
+    }
+}
+
+const BUILTIN_SHORTCUTS: &[BuiltinShortcut] = &[
+    BuiltinShortcut {
+        accelerator: "CmdOrCtrl+Alt+Shift+M",
+        event_name: "shortcut://toggle-window",
+        action: Action::ToggleWindow,
+        description: "Show or hide MIST",
+        category: "Window",
+    },
+    BuiltinShortcut {
+        accelerator: "CmdOrCtrl+Alt+Shift+R",
+        event_name: "shortcut://restart-core",
+        action: Action::RestartCore,
+        description: "Restart the core",
+        category: "Core",
+    },
+    // Tauri only links the inspector into debug builds.
+    #[cfg(debug_assertions)]
+    BuiltinShortcut {
+        accelerator: "CmdOrCtrl+Alt+Shift+I",
+        event_name: "shortcut://toggle-devtools",
+        action: Action::ToggleDevtools,
+        description: "Toggle developer tools",
+        category: "Developer",
+    },
+];
+
+const CUSTOM_CATEGORY: &str = "Custom";
+
+/// Register the shortcuts saved in the `[shortcuts]` config table, then
+/// the built-in ones. One that fails to register (e.g. taken by another
+/// app) is skipped.
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), MistError> {
+    let restored = register_saved(app);
+    let unbound = restored.as_ref().cloned().unwrap_or_default();
+    register_builtin_shortcuts(app, &unbound);
+    restored.map(drop)
+}
+
+/// Returns the keys of accelerators saved as unbound.
+fn register_saved<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<String>, MistError> {
+    let config = config::load(app)?;
+    let mut unbound = Vec::new();
+    let Some(section) = config.get("shortcuts").and_then(|s| s.as_table()) else {
+        return Ok(unbound);
+    };
+    let state = app.state::<Shortcuts>();
+    let mut shortcuts = state.0.lock()?;
+    for (accelerator, event_name) in section {
+        let Some(event_name) = event_name.as_str() else {
+            tracing::warn!(%accelerator, "ignoring non-string [shortcuts] value");
+            continue;
+        };
+        let id = key(accelerator);
+        if event_name.is_empty() {
+            unbound.push(id);
+            continue;
+        }
+        if shortcuts.contains_key(&id) {
+            tracing::warn!(%accelerator, "ignoring duplicate [shortcuts] entry");
+            continue;
+        }
+        match register(app, accelerator, event_name) {
+            Ok(()) => {
+                let binding = Binding {
+                    accelerator: accelerator.clone(),
+                    event_name: event_name.to_string(),
+                };
+                shortcuts.insert(id, binding);
+            }
+            Err(e) => tracing::warn!(%accelerator, "could not register shortcut: {e}"),
+        }
+    }
+    Ok(unbound)
+}
+
+/// Emit `event_name` whenever `accelerator` is pressed, replacing any
+/// previous binding for it, however that one was spelled. A built-in
+/// event name also runs its action.
+#[tauri::command]
+#[tracing::instrument(skip(app, state))]
+pub fn set_global_shortcut<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, Shortcuts>,
+    accelerator: String,
+    event_name: String,
+) -> Result<(), MistError> {
+    check_event_name(&event_name)?;
+    let id = key(&accelerator);
+    let mut shortcuts = state.0.lock()?;
+    // The map only changes once both the registration and the config
+    // write have succeeded; a failure puts the old binding back.
+    let previous = shortcuts.get(&id).cloned();
+    if let Some(previous) = &previous {
+        unregister(&app, &previous.accelerator)?;
+    }
+    if let Err(e) = register(&app, &accelerator, &event_name) {
+        restore(&app, &mut shortcuts, &id, previous.as_ref());
+        return Err(e);
+    }
+    let saved = config::update(&app, |doc| {
+        if let Some(section) = doc["shortcuts"]
+            .or_insert(toml_edit::table())
+            .as_table_like_mut()
+        {
+            forget(section, &id);
+            section.insert(&accelerator, toml_edit::value(&event_name));
+        }
+    });
+    if let Err(e) = saved {
+        if let Err(e) = unregister(&app, &accelerator) {
+            tracing::warn!(%accelerator, "could not roll back shortcut: {e}");
+        }
+        restore(&app, &mut shortcuts, &id, previous.as_ref());
+        return Err(e);
+    }
+    shortcuts.insert(
+        id,
+        Binding {
+            accelerator,
+            event_name,
+        },
+    );
+    Ok(())
+}
+
+/// Stop listening for `accelerator`. A built-in default stays unbound
+/// across restarts.
+#[tauri::command]
+#[tracing::instrument(skip(app, state))]
+pub fn remove_global_shortcut<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, Shortcuts>,
+    accelerator: String,
+) -> Result<(), MistError> {
+    let id = key(&accelerator);
+    let mut shortcuts = state.0.lock()?;
+    let Some(previous) = shortcuts.get(&id).cloned() else {
+        return Err(MistError::Validation(format!(
+            "no shortcut registered for {accelerator:?}"
+        )));
+    };
+    unregister(&app, &previous.accelerator)?;
+    let default = BUILTIN_SHORTCUTS
+        .iter()
+        .any(|shortcut| key(shortcut.accelerator) == id);
+    let saved = config::update(&app, |doc| {
+        if default {
+            if let Some(section) = doc["shortcuts"]
+                .or_insert(toml_edit::table())
+                .as_table_like_mut()
+            {
+                forget(section, &id);
+                section.insert(&previous.accelerator, toml_edit::value(""));
+            }
+        } else if let Some(section) = doc.get_mut("shortcuts").and_then(|s| s.as_table_like_mut()) {
+            forget(section, &id);
+        }
+    });
+    if let Err(e) = saved {
+        restore(&app, &mut shortcuts, &id, Some(&previous));
+        return Err(e);
+    }
+    shortcuts.remove(&id);
+    Ok(())
+}
+
+/// All registered shortcuts, sorted by accelerator.
+#[tauri::command]
+#[tracing::instrument(skip(state))]
+pub fn list_global_shortcuts(state: State<'_, Shortcuts>) -> Vec<ShortcutEntry> {
+    let shortcuts = state.0.lock().unwrap_or_else(|e| e.into_inner());
+    let mut entries: Vec<_> = shortcuts
+        .values()
+        .map(|binding| ShortcutEntry {
+            accelerator: binding.accelerator.clone(),
+            event_name: binding.event_name.clone(),
+        })
+        .collect();
+    entries.sort_by(|a, b| a.accelerator.cmp(&b.accelerator));
+    entries
+}
+
+/// Every shortcut for the help overlay: the registered ones and any
+/// others in the `[shortcuts]` config table, sorted by category then
+/// accelerator.
+#[tauri::command]
+#[tracing::instrument(skip(app, state))]
+pub fn keyboard_shortcut_cheatsheet<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, Shortcuts>,
+) -> Vec<ShortcutDescription> {
+    let registered: Vec<_> = state
+        .0
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .map(|binding| describe(&binding.accelerator, &binding.event_name))
+        .collect();
+    let configured = match config::load(&app) {
+        Ok(config) => config
+            .get("shortcuts")
+            .and_then(|s| s.as_table())
+            .into_iter()
+            .flatten()
+            .filter_map(|(accelerator, event_name)| {
+                let event_name = event_name.as_str().filter(|name| !name.is_empty())?;
+                Some(describe(accelerator, event_name))
+            })
+            .collect(),
+        Err(e) => {
+            tracing::warn!("could not read [shortcuts] config: {e}");
+            Vec::new()
+        }
+    };
+    merge(registered.into_iter().chain(configured))
+}
+
+/// Reject names that `emit` would refuse.
+pub fn check_event_name(event_name: &str) -> Result<(), MistError> {
+    // Tauri rejects any other characters in event names.
+    let valid = |c: char| c.is_alphanumeric() || matches!(c, '-' | '/' | ':' | '_');
+    if event_name.is_empty() || !event_name.chars().all(valid) {
+        return Err(MistError::Validation(format!(
+            "invalid event name {event_name:?}"
+        )));
+    }
+    Ok(())
+}
+
+/// Register the built-in shortcuts whose accelerators are still free and
+/// not in `unbound`.
+fn register_builtin_shortcuts<R: Runtime>(app: &AppHandle<R>, unbound: &[String]) {
+    let state = app.state::<Shortcuts>();
+    let mut shortcuts = state.0.lock().unwrap_or_else(|e| e.into_inner());
+    for shortcut in BUILTIN_SHORTCUTS {
+        let id = key(shortcut.accelerator);
+        if shortcuts.contains_key(&id) || unbound.contains(&id) {
+            tracing::debug!(
+                accelerator = shortcut.accelerator,
+                "built-in shortcut overridden"
+            );
+            continue;
+        }
+        match register(app, shortcut.accelerator, shortcut.event_name) {
+            Ok(()) => {
+                let binding = Binding {
+                    accelerator: shortcut.accelerator.into(),
+                    event_name: shortcut.event_name.into(),
+                };
+                shortcuts.insert(id, binding);
+            }
+            Err(e) => tracing::warn!(
+                accelerator = shortcut.accelerator,
+                "could not register built-in shortcut: {e}"
+            ),
+        }
+    }
+}
+
+fn builtin(event_name: &str) -> Option<&'static BuiltinShortcut> {
+    BUILTIN_SHORTCUTS
+        .iter()
+        .find(|shortcut| shortcut.event_name == event_name)
+}
+
+fn describe(accelerator: &str, event_name: &str) -> ShortcutDescription {
+    let (description, category) = match builtin(event_name) {
+        Some(shortcut) => (shortcut.description.into(), shortcut.category),
+        None => (format!("Emits {event_name}"), CUSTOM_CATEGORY),
+    };
+    ShortcutDescription {
+        accelerator: accelerator.into(),
+        description,
+        category: category.into(),
+    }
+}
+
+/// Remove every spelling of the accelerator with key `id` from the
+/// `[shortcuts]` table.
+fn forget(section: &mut dyn toml_edit::TableLike, id: &str) {
+    let spellings: Vec<_> = section
+        .iter()
+        .map(|(accelerator, _)| accelerator.to_string())
+        .filter(|accelerator| key(accelerator) == id)
+        .collect();
+    for accelerator in spellings {
+        section.remove(&accelerator);
+    }
+}
+
+/// Drop later duplicates of an accelerator, however it is spelled, and
+/// sort by category then accelerator.
+fn merge(shortcuts: impl IntoIterator<Item = ShortcutDescription>) -> Vec<ShortcutDescription> {
+    let mut seen = Vec::new();
+    let mut merged: Vec<_> = shortcuts
+        .into_iter()
+        .filter(|shortcut| {
+            let key = key(&shortcut.accelerator);
+            let new = !seen.contains(&key);
+            seen.push(key);
+            new
+        })
+        .collect();
+    merged.sort_by(|a, b| (&a.category, &a.accelerator).cmp(&(&b.category, &b.accelerator)));
+    merged
+}
+
+/// `accelerator` in a canonical spelling, e.g. `ctrl+shift+m` and
+/// `Shift+Control+M` both become the same string.
+fn key(accelerator: &str) -> String {
+    accelerator
+        .parse::<Shortcut>()
+        .map(Shortcut::into_string)
+        .unwrap_or_else(|_| accelerator.to_lowercase())
+}
+
+fn register<R: Runtime>(
+    app: &AppHandle<R>,
+    accelerator: &str,
+    event_name: &str,
+) -> Result<(), MistError> {
+    let action = builtin(event_name).map(|shortcut| shortcut.action);
+    let event_name = event_name.to_string();
+    app.global_shortcut()
+        .on_shortcut(accelerator, move |app, _, event| {
+            if event.state() == ShortcutState::Pressed {
+                if let Some(action) = action {
+                    action.run(app);
+                }
+                let _ = app.emit(&event_name, ());
+            }
+        })
+        .map_err(|e| MistError::Process(format!("failed to register {accelerator:?}: {e}")))
+}
+
+fn unregister<R: Runtime>(app: &AppHandle<R>, accelerator: &str) -> Result<(), MistError> {
+    app.global_shortcut()
+        .unregister(accelerator)
+        .map_err(|e| MistError::Process(format!("failed to unregister {accelerator:?}: {e}")))
+}
+
+/// Re-register `previous` under `id` after a failed change. If that
+/// fails too the binding is gone, so it is dropped from the map.
+fn restore<R: Runtime>(
+    app: &AppHandle<R>,
+    shortcuts: &mut HashMap<String, Binding>,
+    id: &str,
+    previous: Option<&Binding>,
+) {
+    let Some(previous) = previous else {
+        return;
+    };
+    if let Err(e) = register(app, &previous.accelerator, &previous.event_name) {
+        tracing::warn!(accelerator = %previous.accelerator, "could not restore shortcut: {e}");
+        shortcuts.remove(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shortcut(accelerator: &str, category: &str) -> ShortcutDescription {
+        ShortcutDescription {
+            accelerator: accelerator.into(),
+            description: format!("{category} {accelerator}"),
+            category: category.into(),
+        }
+    }
+
+    #[test]
+    fn keys_ignore_spelling() {
+        assert_eq!(key("ctrl+shift+k"), key("Shift+Control+K"));
+        assert_ne!(key("Ctrl+Shift+K"), key("Ctrl+K"));
+    }
+
+    #[test]
+    fn forgets_every_spelling() {
+        let mut doc: toml_edit::DocumentMut =
+            "[shortcuts]\n\"ctrl+shift+k\" = \"a\"\n\"Shift+Control+K\" = \"b\"\n\"Ctrl+K\" = \"c\"\n"
+                .parse()
+                .unwrap();
+        let section = doc["shortcuts"].as_table_like_mut().unwrap();
+        forget(section, &key("Ctrl+Shift+K"));
+        assert_eq!(doc.to_string(), "[shortcuts]\n\"Ctrl+K\" = \"c\"\n");
+    }
+
+    #[test]
+    fn describes_builtin_events() {
+        assert_eq!(
+            describe("Alt+F1", "shortcut://restart-core"),
+            ShortcutDescription {
+                accelerator: "Alt+F1".into(),
+                description: "Restart the core".into(),
+                category: "Core".into(),
+            }
+        );
+        assert_eq!(describe("Alt+F2", "my-event").category, CUSTOM_CATEGORY);
+    }
+
+    #[test]
+    fn merges_duplicates_and_sorts_by_category() {
+        let merged = merge([
+            shortcut("Ctrl+Shift+K", "Custom"),
+            shortcut("Alt+F1", "Custom"),
+            shortcut("Ctrl+Shift+M", "Window"),
+            shortcut("Ctrl+Shift+R", "Core"),
+            // Same keys as earlier entries, spelled differently.
+            shortcut("shift+control+k", "Window"),
+            shortcut("Alt+F1", "Custom"),
+        ]);
+        assert_eq!(
+            merged,
+            [
+                shortcut("Ctrl+Shift+R", "Core"),
+                shortcut("Alt+F1", "Custom"),
+                shortcut("Ctrl+Shift+K", "Custom"),
+                shortcut("Ctrl+Shift+M", "Window"),
+            ]
+        );
+    }
+}
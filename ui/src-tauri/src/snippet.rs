@@ -0,0 +1,151 @@
+// Ad-hoc Python snippets run from the frontend.
+//
+// A snippet is run as `python -c <code>` with the same interpreter as the
+// core. stdout and stderr share one pipe so the output interleaves the
+// way it would in a terminal. The pipe is drained on the blocking pool
+// while the child runs, so a chatty snippet cannot stall on a full pipe;
+// anything past `MAX_OUTPUT` is read and discarded.
+
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use tauri::{AppHandle, Runtime};
+use tokio::process::Command;
+
+use crate::{core_process, error::MistError};
+
+/// Longest snippet accepted, in bytes.
+const MAX_CODE_LEN: usize = 4096;
+
+/// Output beyond this many bytes is dropped.
+const MAX_OUTPUT: usize = 64 * 1024;
+
+/// Run `code` with the bundled Python and return its combined output.
+/// The process is killed if it runs longer than `timeout_ms`.
+#[tauri::command]
+#[tracing::instrument(skip(app, code), fields(code_len = code.len()))]
+pub async fn run_python_snippet<R: Runtime>(
+    app: AppHandle<R>,
+    code: String,
+    timeout_ms: u64,
+) -> Result<String, MistError> {
+    if code.len() > MAX_CODE_LEN {
+        return Err(MistError::Validation(format!(
+            "snippet exceeds {MAX_CODE_LEN} bytes"
+        )));
+    }
+    let python = core_process::python_path(&app);
+    run(python, &code, Duration::from_millis(timeout_ms)).await
+}
+
+async fn run(python: PathBuf, code: &str, timeout: Duration) -> Result<String, MistError> {
+    let (reader, writer) = io::pipe()?;
+    let mut child = {
+        let stdout = writer.try_clone()?;
+        Command::new(&python)
+            .arg("-c")
+            .arg(code)
+            .stdin(Stdio::null())
+            .stdout(stdout)
+            .stderr(writer)
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| MistError::Process(format!("failed to spawn {}: {e}", python.display())))?
+        // The `Command`, and with it our copies of the write end, is
+        // dropped here so the reader sees EOF once the child exits.
+    };
+    let output = tauri::async_runtime::spawn_blocking(move || read_capped(reader));
+
+    match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(status) => {
+            status?;
+        }
+        Err(_) => {
+            let _ = child.kill().await;
+            return Err(MistError::Timeout);
+        }
+    }
+    let bytes = output.await?.map_err(|e| {
+        MistError::Io(format!(
+            "failed to read output of {}: {e}",
+            python.display()
+        ))
+    })?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Read `reader` to EOF, keeping at most `MAX_OUTPUT` bytes.
+fn read_capped(mut reader: impl Read) -> io::Result<Vec<u8>> {
+    let mut kept = Vec::new();
+    let mut buf = [0; 8192];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => return Ok(kept),
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        let room = MAX_OUTPUT - kept.len();
+        kept.extend_from_slice(&buf[..n.min(room)]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PYTHON: &str = if cfg!(windows) { "python" } else { "python3" };
+
+    fn snippet(code: &str, timeout: Duration) -> Result<String, MistError> {
+        tauri::async_runtime::block_on(run(PYTHON.into(), code, timeout))
+    }
+
+    #[test]
+    fn returns_stdout() {
+        assert_eq!(
+            snippet("print(1+1)", Duration::from_secs(10)).unwrap(),
+            "2\n"
+        );
+    }
+
+    #[test]
+    fn captures_stderr() {
+        let out = snippet(
+            "import sys; sys.stderr.write('oops')",
+            Duration::from_secs(10),
+        );
+        assert_eq!(out.unwrap(), "oops");
+    }
+
+    #[test]
+    fn times_out() {
+        let out = snippet("import time; time.sleep(10)", Duration::from_millis(200));
+        assert_eq!(out.unwrap_err(), MistError::Timeout);
+    }
+
+    #[test]
+    fn caps_output() {
+        let out = snippet("print('x' * 100000)", Duration::from_secs(10)).unwrap();
+        assert_eq!(out.len(), MAX_OUTPUT);
+    }
+
+    #[test]
+    fn replaces_invalid_utf8() {
+        let out = snippet(
+            "import sys; sys.stdout.buffer.write(b'a\\xffb')",
+            Duration::from_secs(10),
+        );
+        assert_eq!(out.unwrap(), "a\u{fffd}b");
+    }
+
+    #[test]
+    fn rejects_long_code() {
+        let app = tauri::test::mock_app();
+        let code = format!("# {}", "x".repeat(MAX_CODE_LEN));
+        let out =
+            tauri::async_runtime::block_on(run_python_snippet(app.handle().clone(), code, 1000));
+        assert!(out.is_err());
+    }
+}
@@ -0,0 +1,171 @@
+// Read-only queries against local SQLite databases, so users can inspect
+// MIST outputs without a separate DB browser.
+//
+// Only `SELECT` statements are accepted, judged by the query's first
+// word, and the database is opened read-only as well. Parameters are
+// bound positionally as text, and a result set may hold at most 10 000
+// rows.
+
+use std::collections::HashMap;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rusqlite::types::ValueRef;
+use rusqlite::{params_from_iter, Connection, OpenFlags};
+use serde_json::Value;
+
+use crate::error::MistError;
+
+const MAX_ROWS: usize = 10_000;
+
+/// One result row, keyed by column name.
+pub type Row = HashMap<String, Value>;
+
+/// Run the `SELECT` statement `query` against the database at `db_path`,
+/// binding `params` to its `?` placeholders in order. BLOBs come back as
+/// base64 strings; if two columns share a name the later one wins.
+#[tauri::command]
+#[tracing::instrument(skip(params))]
+pub async fn execute_sql(
+    db_path: String,
+    query: String,
+    params: Vec<String>,
+) -> Result<Vec<Row>, MistError> {
+    check_select(&query)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let conn = Connection::open_with_flags(
+            &db_path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(|e| MistError::Io(format!("failed to open {db_path}: {e}")))?;
+        select(&conn, &query, &params)
+    })
+    .await?
+}
+
+fn check_select(query: &str) -> Result<(), MistError> {
+    let first: String = query
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect();
+    if !first.eq_ignore_ascii_case("select") {
+        return Err(MistError::Validation(
+            "only SELECT queries are permitted".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn select(conn: &Connection, query: &str, params: &[String]) -> Result<Vec<Row>, MistError> {
+    let invalid = |e: rusqlite::Error| MistError::Validation(format!("invalid query: {e}"));
+    let failed = |e: rusqlite::Error| MistError::Io(format!("query failed: {e}"));
+
+    let mut statement = conn.prepare(query).map_err(invalid)?;
+    let columns: Vec<String> = statement
+        .column_names()
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let mut rows = statement.query(params_from_iter(params)).map_err(invalid)?;
+    let mut out = Vec::new();
+    while let Some(row) = rows.next().map_err(failed)? {
+        if out.len() == MAX_ROWS {
+            return Err(MistError::Validation(format!(
+                "query returned more than {MAX_ROWS} rows"
+            )));
+        }
+        let mut values = Row::with_capacity(columns.len());
+        for (i, name) in columns.iter().enumerate() {
+            values.insert(name.clone(), to_json(row.get_ref(i).map_err(failed)?));
+        }
+        out.push(values);
+    }
+    Ok(out)
+}
+
+fn to_json(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => i.into(),
+        // JSON has no NaN or infinity.
+        ValueRef::Real(f) => serde_json::Number::from_f64(f).map_or(Value::Null, Value::Number),
+        ValueRef::Text(text) => String::from_utf8_lossy(text).into_owned().into(),
+        ValueRef::Blob(blob) => STANDARD.encode(blob).into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    fn sample_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE runs (id INTEGER, score REAL, label TEXT, raw BLOB, note TEXT);
+             INSERT INTO runs VALUES (1, 0.5, 'baseline', x'6d697374', NULL);
+             INSERT INTO runs VALUES (2, 0.75, 'tuned', x'', 'best');",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn only_select_is_permitted() {
+        assert!(check_select("  select * from runs").is_ok());
+        assert!(check_select("SELECT*FROM runs").is_ok());
+        for query in [
+            "DELETE FROM runs",
+            "selector",
+            "WITH x AS (SELECT 1) SELECT 1",
+            "",
+        ] {
+            let err = check_select(query).unwrap_err();
+            assert_eq!(
+                err,
+                MistError::Validation("only SELECT queries are permitted".into())
+            );
+        }
+    }
+
+    #[test]
+    fn converts_column_types() {
+        let rows = select(
+            &sample_db(),
+            "SELECT * FROM runs WHERE label = ?",
+            &["baseline".into()],
+        )
+        .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["id"], json!(1));
+        assert_eq!(rows[0]["score"], json!(0.5));
+        assert_eq!(rows[0]["label"], json!("baseline"));
+        assert_eq!(rows[0]["raw"], json!("bWlzdA=="));
+        assert_eq!(rows[0]["note"], Value::Null);
+    }
+
+    #[test]
+    fn rejects_wrong_parameter_count() {
+        let err = select(&sample_db(), "SELECT * FROM runs WHERE id = ?", &[]).unwrap_err();
+        assert!(matches!(err, MistError::Validation(_)));
+    }
+
+    #[test]
+    fn caps_result_rows() {
+        let count = |n: usize| {
+            format!(
+                "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n LIMIT {n})
+                 SELECT x FROM n"
+            )
+        };
+        let conn = Connection::open_in_memory().unwrap();
+        assert_eq!(
+            select(&conn, &count(MAX_ROWS), &[]).unwrap().len(),
+            MAX_ROWS
+        );
+        let err = select(&conn, &count(MAX_ROWS + 1), &[]).unwrap_err();
+        assert!(matches!(&err, MistError::Validation(m) if m.contains("more than")));
+    }
+}
@@ -0,0 +1,55 @@
+// Host diagnostics for bug reports.
+
+use serde::Serialize;
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
+use tauri::{AppHandle, Runtime};
+
+use crate::error::MistError;
+
+#[derive(Debug, Serialize)]
+pub struct SystemInfo {
+    pub os_name: String,
+    pub os_version: String,
+    pub cpu_count: usize,
+    pub total_memory_mb: u64,
+    pub app_version: String,
+}
+
+/// Describe the host OS and hardware along with the app version.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub async fn get_system_info<R: Runtime>(app: AppHandle<R>) -> Result<SystemInfo, MistError> {
+    let app_version = app.package_info().version.to_string();
+    Ok(tauri::async_runtime::spawn_blocking(move || collect(app_version)).await?)
+}
+
+pub fn collect(app_version: String) -> SystemInfo {
+    let sys = System::new_with_specifics(
+        RefreshKind::nothing()
+            .with_cpu(CpuRefreshKind::nothing())
+            .with_memory(MemoryRefreshKind::nothing().with_ram()),
+    );
+    SystemInfo {
+        os_name: std::env::consts::OS.to_string(),
+        os_version: System::os_version().unwrap_or_default(),
+        cpu_count: sys.cpus().len(),
+        total_memory_mb: sys.total_memory() / (1024 * 1024),
+        app_version,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn system_info_serialises_to_json() {
+        let info = collect("2.0.0".into());
+        assert!(info.cpu_count > 0);
+
+        let json: serde_json::Value = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["os_name"], std::env::consts::OS);
+        assert_eq!(json["app_version"], "2.0.0");
+        assert!(json["cpu_count"].as_u64().unwrap() > 0);
+    }
+}
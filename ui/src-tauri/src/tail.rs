@@ -0,0 +1,193 @@
+// In-app log viewing: read the last lines of a file and follow it as it
+// grows.
+//
+// Each watcher remembers the byte offset it has reported up to and only
+// emits complete lines; a trailing partial line is held back until its
+// newline arrives. If the file shrinks (truncated or rotated in place)
+// the offset resets to the start.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime, State};
+
+use crate::error::MistError;
+
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Active `watch_file` watchers, keyed by caller-chosen id.
+#[derive(Default)]
+pub struct FileWatchers(Mutex<HashMap<String, RecommendedWatcher>>);
+
+#[derive(Clone, Serialize)]
+struct Changed {
+    id: String,
+    new_lines: Vec<String>,
+}
+
+/// Last `lines` lines of the file at `path`.
+#[tauri::command]
+#[tracing::instrument]
+pub fn tail_file(path: String, lines: u32) -> Result<Vec<String>, MistError> {
+    let mut file =
+        File::open(&path).map_err(|e| MistError::Io(format!("failed to open {path}: {e}")))?;
+    tail(&mut file, lines as usize)
+        .map_err(|e| MistError::Io(format!("failed to read {path}: {e}")))
+}
+
+/// Emit `file://changed` with the new lines each time the file at `path`
+/// grows. Reusing an `id` replaces its previous watcher.
+#[tauri::command]
+#[tracing::instrument(skip(app, watchers))]
+pub fn watch_file<R: Runtime>(
+    app: AppHandle<R>,
+    watchers: State<'_, FileWatchers>,
+    path: String,
+    id: String,
+) -> Result<(), MistError> {
+    let path = PathBuf::from(path);
+    let mut offset = path
+        .metadata()
+        .map_err(|e| MistError::Io(format!("failed to stat {}: {e}", path.display())))?
+        .len();
+
+    let target = path.clone();
+    let event_id = id.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if !(event.kind.is_modify() || event.kind.is_create()) {
+            return;
+        }
+        match read_new_lines(&target, &mut offset) {
+            Ok(new_lines) if new_lines.is_empty() => {}
+            Ok(new_lines) => {
+                let _ = app.emit(
+                    "file://changed",
+                    Changed {
+                        id: event_id.clone(),
+                        new_lines,
+                    },
+                );
+            }
+            Err(e) => tracing::warn!(path = %target.display(), "failed to read: {e}"),
+        }
+    })
+    .map_err(|e| MistError::Io(e.to_string()))?;
+    watcher
+        .watch(&path, RecursiveMode::NonRecursive)
+        .map_err(|e| MistError::Io(format!("failed to watch {}: {e}", path.display())))?;
+
+    watchers.0.lock()?.insert(id, watcher);
+    Ok(())
+}
+
+/// Stop the watcher registered under `id`.
+#[tauri::command]
+#[tracing::instrument(skip(watchers))]
+pub fn unwatch_file(watchers: State<'_, FileWatchers>, id: String) -> Result<(), MistError> {
+    watchers
+        .0
+        .lock()?
+        .remove(&id)
+        .map(drop)
+        .ok_or_else(|| MistError::Validation(format!("no watcher with id {id:?}")))
+}
+
+/// Read backwards from the end of `file` until more than `lines` newlines
+/// have been seen (or the start is reached), then return the last `lines`
+/// lines.
+fn tail(file: &mut File, lines: usize) -> io::Result<Vec<String>> {
+    let mut pos = file.seek(SeekFrom::End(0))?;
+    let mut chunks = Vec::new();
+    let mut newlines = 0;
+    let mut buf = vec![0; CHUNK_SIZE];
+    while pos > 0 && newlines <= lines {
+        let n = pos.min(CHUNK_SIZE as u64) as usize;
+        pos -= n as u64;
+        file.seek(SeekFrom::Start(pos))?;
+        file.read_exact(&mut buf[..n])?;
+        newlines += buf[..n].iter().filter(|&&b| b == b'\n').count();
+        chunks.push(buf[..n].to_vec());
+    }
+    let bytes: Vec<u8> = chunks.into_iter().rev().flatten().collect();
+    let text = String::from_utf8_lossy(&bytes);
+    let all: Vec<&str> = text.lines().collect();
+    let start = all.len().saturating_sub(lines);
+    Ok(all[start..].iter().map(|line| line.to_string()).collect())
+}
+
+/// Complete lines appended to `path` since `offset`, advancing `offset`
+/// past them.
+fn read_new_lines(path: &Path, offset: &mut u64) -> io::Result<Vec<String>> {
+    let mut file = File::open(path)?;
+    if file.metadata()?.len() < *offset {
+        *offset = 0;
+    }
+    file.seek(SeekFrom::Start(*offset))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    let Some(end) = bytes.iter().rposition(|&b| b == b'\n') else {
+        return Ok(Vec::new());
+    };
+    *offset += end as u64 + 1;
+    Ok(String::from_utf8_lossy(&bytes[..end])
+        .lines()
+        .map(String::from)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+
+    use tempfile::NamedTempFile;
+
+    /// A file holding `contents`, deleted when the handle drops.
+    fn scratch_file(contents: &str) -> NamedTempFile {
+        let file = NamedTempFile::new().unwrap();
+        fs::write(file.path(), contents).unwrap();
+        file
+    }
+
+    #[test]
+    fn tail_returns_last_lines() {
+        let contents: String = (0..5000).map(|i| format!("line {i}\n")).collect();
+        let scratch = scratch_file(&contents);
+        let mut file = File::open(scratch.path()).unwrap();
+
+        assert_eq!(tail(&mut file, 2).unwrap(), ["line 4998", "line 4999"]);
+        assert_eq!(tail(&mut file, 0).unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn tail_of_short_file_returns_everything() {
+        let scratch = scratch_file("a\nb");
+        let mut file = File::open(scratch.path()).unwrap();
+
+        assert_eq!(tail(&mut file, 10).unwrap(), ["a", "b"]);
+    }
+
+    #[test]
+    fn new_lines_hold_back_partial_line() {
+        let scratch = scratch_file("old\n");
+        let path = scratch.path();
+        let mut offset = 4;
+        let mut file = OpenOptions::new().append(true).open(path).unwrap();
+
+        file.write_all(b"one\ntw").unwrap();
+        assert_eq!(read_new_lines(path, &mut offset).unwrap(), ["one"]);
+        file.write_all(b"o\n").unwrap();
+        assert_eq!(read_new_lines(path, &mut offset).unwrap(), ["two"]);
+        assert!(read_new_lines(path, &mut offset).unwrap().is_empty());
+    }
+}
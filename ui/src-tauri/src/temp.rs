@@ -0,0 +1,181 @@
+// Scratch files for handing large blobs from the frontend to the core.
+//
+// Files live in `app_cache_dir()/mist_tmp/` and are swept by
+// `cleanup_temp_files` once they are more than an hour old.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::MistError;
+
+const TEMP_DIR: &str = "mist_tmp";
+const MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Write `content` to `mist_tmp/<filename>` and return its absolute path.
+#[tauri::command]
+#[tracing::instrument(skip(app, content))]
+pub fn write_temp_file<R: Runtime>(
+    app: AppHandle<R>,
+    filename: String,
+    content: String,
+) -> Result<String, MistError> {
+    check_filename(&filename)?;
+    let path = write_into(&temp_dir(&app)?, &filename, &content)?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Delete temp files older than an hour. Returns how many were removed.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub fn cleanup_temp_files<R: Runtime>(app: AppHandle<R>) -> Result<u32, MistError> {
+    let dir = temp_dir(&app)?;
+    if !dir.exists() {
+        return Ok(0);
+    }
+    remove_older_than(&dir, MAX_AGE, SystemTime::now())
+}
+
+fn temp_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, MistError> {
+    let cache = app.path().app_cache_dir()?;
+    Ok(cache.join(TEMP_DIR))
+}
+
+/// Reject names that could escape the temp directory.
+fn check_filename(filename: &str) -> Result<(), MistError> {
+    if filename.is_empty() || filename.contains("..") || filename.contains(['/', '\\']) {
+        return Err(MistError::Validation(format!(
+            "invalid temp file name: {filename:?}"
+        )));
+    }
+    Ok(())
+}
+
+fn write_into(dir: &Path, filename: &str, content: &str) -> Result<PathBuf, MistError> {
+    fs::create_dir_all(dir)
+        .map_err(|e| MistError::Io(format!("failed to create {}: {e}", dir.display())))?;
+    let path = dir.join(filename);
+    fs::write(&path, content)
+        .map_err(|e| MistError::Io(format!("failed to write {}: {e}", path.display())))?;
+    Ok(path)
+}
+
+fn remove_older_than(dir: &Path, max_age: Duration, now: SystemTime) -> Result<u32, MistError> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| MistError::Io(format!("failed to read {}: {e}", dir.display())))?;
+    let mut removed = 0;
+    for entry in entries.filter_map(Result::ok) {
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if !meta.is_file() {
+            continue;
+        }
+        let age = meta
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        if age > max_age {
+            match fs::remove_file(entry.path()) {
+                Ok(()) => removed += 1,
+                Err(e) => tracing::warn!(path = %entry.path().display(), "failed to remove: {e}"),
+            }
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs::File;
+
+    use tauri::test::{get_ipc_response, mock_builder, mock_context, noop_assets};
+    use tauri::WebviewWindowBuilder;
+
+    use crate::test_util::request;
+
+    #[test]
+    fn rejects_unsafe_filenames() {
+        for name in ["../escape.csv", "a/b.csv", "a\\b.csv", "..", ""] {
+            assert!(
+                matches!(check_filename(name), Err(MistError::Validation(_))),
+                "{name:?} should be rejected"
+            );
+        }
+        assert!(check_filename("upload.csv").is_ok());
+    }
+
+    #[test]
+    fn writes_into_temp_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join(TEMP_DIR);
+        let path = write_into(&dir, "upload.csv", "a,b\n1,2\n").unwrap();
+
+        assert_eq!(path, dir.join("upload.csv"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a,b\n1,2\n");
+    }
+
+    #[test]
+    fn commands_use_the_app_cache_dir() {
+        let app = mock_builder()
+            .invoke_handler(tauri::generate_handler![
+                write_temp_file,
+                cleanup_temp_files
+            ])
+            .build(mock_context(noop_assets()))
+            .unwrap();
+        let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+            .build()
+            .unwrap();
+        let filename = format!("upload-{}.csv", std::process::id());
+
+        let path = get_ipc_response(
+            &webview,
+            request(
+                "write_temp_file",
+                serde_json::json!({ "filename": filename, "content": "a,b\n" }),
+            ),
+        )
+        .unwrap()
+        .deserialize::<String>()
+        .unwrap();
+        let path = PathBuf::from(path);
+        let expected = app.path().app_cache_dir().unwrap().join(TEMP_DIR);
+        assert_eq!(path, expected.join(&filename));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a,b\n");
+
+        // The file is fresh, so the sweep leaves it alone.
+        get_ipc_response(
+            &webview,
+            request("cleanup_temp_files", serde_json::json!({})),
+        )
+        .unwrap()
+        .deserialize::<u32>()
+        .unwrap();
+        assert!(path.exists());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn cleanup_removes_only_stale_files() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path();
+        let now = SystemTime::now();
+        let stale = dir.join("stale.csv");
+        let fresh = dir.join("fresh.csv");
+        File::create(&stale)
+            .unwrap()
+            .set_modified(now - Duration::from_secs(2 * 60 * 60))
+            .unwrap();
+        File::create(&fresh).unwrap();
+
+        assert_eq!(remove_older_than(dir, MAX_AGE, now).unwrap(), 1);
+        assert!(!stale.exists());
+        assert!(fresh.exists());
+    }
+}
@@ -0,0 +1,18 @@
+// Helpers shared by the unit tests.
+
+use tauri::ipc::{CallbackFn, InvokeBody};
+use tauri::test::INVOKE_KEY;
+use tauri::webview::InvokeRequest;
+
+/// An IPC request for `cmd`, as the frontend's `invoke` would send it.
+pub fn request(cmd: &str, body: serde_json::Value) -> InvokeRequest {
+    InvokeRequest {
+        cmd: cmd.into(),
+        callback: CallbackFn(0),
+        error: CallbackFn(1),
+        url: "http://tauri.localhost".parse().unwrap(),
+        body: InvokeBody::Json(body),
+        headers: Default::default(),
+        invoke_key: INVOKE_KEY.to_string(),
+    }
+}
@@ -0,0 +1,88 @@
+// System tray icon and menu.
+//
+// The icon mirrors `core://status`: green (filled) once the core is
+// ready, red (hollow) while it is stopping, starting, or has failed.
+// The two variants differ in shape as well as colour so they stay
+// distinguishable as macOS template images.
+//
+// The "Always on top" checkbox follows `window://always-on-top`, so it
+// stays in sync however the setting is changed.
+
+use tauri::image::Image;
+use tauri::menu::{CheckMenuItem, Menu, MenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Listener, Manager, Runtime};
+
+use crate::{core_process, window};
+
+const TRAY_ID: &str = "main";
+
+const ICON_READY: &[u8] = include_bytes!("../icons/tray-green.png");
+const ICON_DOWN: &[u8] = include_bytes!("../icons/tray-red.png");
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    let show = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
+    let hide = MenuItem::with_id(app, "hide", "Hide", true, None::<&str>)?;
+    let on_top = CheckMenuItem::with_id(
+        app,
+        "always_on_top",
+        "Always on top",
+        true,
+        app.state::<window::AlwaysOnTop>().get(),
+        None::<&str>,
+    )?;
+    let restart = MenuItem::with_id(app, "restart_core", "Restart Core", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&show, &hide, &on_top, &restart, &quit])?;
+
+    TrayIconBuilder::with_id(TRAY_ID)
+        .icon(Image::from_bytes(ICON_DOWN)?)
+        .icon_as_template(cfg!(target_os = "macos"))
+        .tooltip("MIST")
+        .menu(&menu)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            "show" => window::show_main(app),
+            "hide" => {
+                if let Some(window) = app.get_webview_window(window::MAIN) {
+                    let _ = window.hide();
+                }
+            }
+            "always_on_top" => {
+                let state = app.state::<window::AlwaysOnTop>();
+                let enable = !state.get();
+                if let Err(e) = window::window_always_on_top(app.clone(), state, enable) {
+                    tracing::warn!("could not toggle always on top: {e}");
+                }
+            }
+            "restart_core" => core_process::spawn_restart(app),
+            "quit" => {
+                core_process::shutdown(app);
+                app.exit(0);
+            }
+            _ => {}
+        })
+        .build(app)?;
+
+    let handle = app.clone();
+    app.listen("core://status", move |event| {
+        let ready = event.payload() == "\"ready\"";
+        set_icon(&handle, if ready { ICON_READY } else { ICON_DOWN });
+    });
+    let handle = app.clone();
+    app.listen("core://error", move |_| set_icon(&handle, ICON_DOWN));
+    app.listen("window://always-on-top", move |event| {
+        let _ = on_top.set_checked(event.payload() == "true");
+    });
+
+    Ok(())
+}
+
+fn set_icon<R: Runtime>(app: &AppHandle<R>, bytes: &[u8]) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    if let Ok(icon) = Image::from_bytes(bytes) {
+        let _ = tray.set_icon(Some(icon));
+        let _ = tray.set_icon_as_template(cfg!(target_os = "macos"));
+    }
+}
@@ -0,0 +1,223 @@
+// In-app updates via `tauri-plugin-updater`.
+//
+// Endpoints come from the `[updater]` table in `config.toml`, falling
+// back to `plugins.updater.endpoints` in tauri.conf.json. Only one check
+// or download runs at a time, and it can be abandoned with
+// `cancel_update`, which drops the underlying request.
+//
+// Installing is refused outright while `plugins.updater.pubkey` is empty,
+// since the signature of the downloaded bundle could not be checked.
+
+use std::future::Future;
+use std::sync::Mutex;
+
+use semver::Version;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime, State, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+use tokio_util::sync::CancellationToken;
+
+use crate::{config, error::MistError};
+
+#[derive(Default)]
+pub struct UpdateState {
+    /// Result of the last successful check, consumed by `install_update`.
+    pending: Mutex<Option<Update>>,
+    /// Token of the check or download in flight, if any.
+    cancel: Mutex<Option<CancellationToken>>,
+}
+
+#[derive(Serialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub notes: Option<String>,
+    pub date: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct Progress {
+    /// `"checking"`, `"checked"` or `"downloading"`.
+    stage: &'static str,
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+/// Ask the update endpoint whether a newer version than this build exists.
+#[tauri::command]
+#[tracing::instrument(skip(app, state))]
+pub async fn check_for_update<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, UpdateState>,
+) -> Result<Option<UpdateInfo>, MistError> {
+    let mut builder = app.updater_builder().version_comparator(|_, remote| {
+        Version::parse(env!("CARGO_PKG_VERSION")).map_or(true, |current| remote.version > current)
+    });
+    let endpoints = configured_endpoints(&app)?;
+    if !endpoints.is_empty() {
+        builder = builder
+            .endpoints(endpoints)
+            .map_err(|e| MistError::Config(e.to_string()))?;
+    }
+    let updater = builder
+        .build()
+        .map_err(|e| MistError::Process(e.to_string()))?;
+
+    emit_progress(&app, "checking", 0, None);
+    let update = cancellable(&state, updater.check())
+        .await?
+        .map_err(|e| MistError::Process(e.to_string()))?;
+    emit_progress(&app, "checked", 0, None);
+    let info = update.as_ref().map(|update| UpdateInfo {
+        version: update.version.clone(),
+        notes: update.body.clone(),
+        date: update.raw_json["pub_date"].as_str().map(String::from),
+    });
+    *state.pending.lock()? = update;
+    Ok(info)
+}
+
+/// Download and install the update found by the last `check_for_update`,
+/// reporting progress on `update://progress`.
+#[tauri::command]
+#[tracing::instrument(skip(app, state))]
+pub async fn install_update<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, UpdateState>,
+) -> Result<(), MistError> {
+    if !has_pubkey(&app) {
+        return Err(MistError::Config(
+            "updates cannot be installed: plugins.updater.pubkey is not set".into(),
+        ));
+    }
+    let update = state.pending.lock()?.take().ok_or_else(|| {
+        MistError::Validation("no update available; call check_for_update first".into())
+    })?;
+
+    let mut downloaded = 0u64;
+    let install = update.download_and_install(
+        |chunk, total| {
+            downloaded += chunk as u64;
+            emit_progress(&app, "downloading", downloaded, total);
+        },
+        || tracing::info!("update downloaded"),
+    );
+    cancellable(&state, install)
+        .await?
+        .map_err(|e| MistError::Process(e.to_string()))
+}
+
+/// Abort an in-progress update check or download.
+#[tauri::command]
+#[tracing::instrument(skip(state))]
+pub fn cancel_update(state: State<'_, UpdateState>) -> Result<(), MistError> {
+    if let Some(token) = state.cancel.lock()?.as_ref() {
+        token.cancel();
+    }
+    Ok(())
+}
+
+/// Run `fut` until it finishes or `cancel_update` is called. Cancelling
+/// drops the future, which aborts any request it has in flight. Fails
+/// straight away if another check or download is still running.
+async fn cancellable<T>(state: &UpdateState, fut: impl Future<Output = T>) -> Result<T, MistError> {
+    let token = {
+        let mut slot = state.cancel.lock()?;
+        if slot.is_some() {
+            return Err(MistError::Validation(
+                "an update check or download is already in progress".into(),
+            ));
+        }
+        slot.insert(CancellationToken::new()).clone()
+    };
+    // Clears the slot however this future ends, including being dropped.
+    let _in_flight = InFlight(state);
+    tokio::select! {
+        out = fut => Ok(out),
+        _ = token.cancelled() => Err(MistError::Cancelled),
+    }
+}
+
+struct InFlight<'a>(&'a UpdateState);
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut slot) = self.0.cancel.lock() {
+            slot.take();
+        }
+    }
+}
+
+fn emit_progress<R: Runtime>(
+    app: &AppHandle<R>,
+    stage: &'static str,
+    downloaded: u64,
+    total: Option<u64>,
+) {
+    let _ = app.emit(
+        "update://progress",
+        Progress {
+            stage,
+            downloaded,
+            total,
+        },
+    );
+}
+
+/// Whether tauri.conf.json has a key to verify update signatures with.
+fn has_pubkey<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("pubkey"))
+        .and_then(|pubkey| pubkey.as_str())
+        .is_some_and(|pubkey| !pubkey.trim().is_empty())
+}
+
+fn configured_endpoints<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<Url>, MistError> {
+    let config = config::load(app)?;
+    let Some(endpoints) = config
+        .get("updater")
+        .and_then(|updater| updater.get("endpoints"))
+        .and_then(|endpoints| endpoints.as_array())
+    else {
+        return Ok(Vec::new());
+    };
+    endpoints
+        .iter()
+        .filter_map(|endpoint| endpoint.as_str())
+        .map(|endpoint| {
+            Url::parse(endpoint)
+                .map_err(|e| MistError::Config(format!("bad update endpoint {endpoint:?}: {e}")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_pubkey_is_detected() {
+        let app = tauri::test::mock_app();
+        assert!(!has_pubkey(app.handle()));
+    }
+
+    #[test]
+    fn rejects_a_second_operation_in_flight() {
+        let state = UpdateState::default();
+        tauri::async_runtime::block_on(async {
+            // `join!` polls the first future, which registers its token and
+            // yields, before the second one starts.
+            let (first, second) = tokio::join!(
+                cancellable(&state, tokio::task::yield_now()),
+                cancellable(&state, async {}),
+            );
+            assert!(first.is_ok());
+            assert!(matches!(second, Err(MistError::Validation(_))));
+
+            // The slot is free again once the first one has finished.
+            assert!(cancellable(&state, async {}).await.is_ok());
+        });
+    }
+}
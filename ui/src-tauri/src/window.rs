@@ -0,0 +1,532 @@
+// Main window helpers: focus, geometry persistence, close behaviour.
+//
+// Geometry is saved to the `[window]` table of config.toml when the main
+// window is closed and restored in `setup`, clamped so the window lands
+// on a monitor that still exists. The same table holds
+// `minimize_to_tray`, which turns the close button into "hide",
+// `always_on_top`, and `title_template`, whose `{key}` placeholders are
+// filled in to build the window title. At startup `{project}` comes from
+// `[project] name`.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{
+    AppHandle, CloseRequestApi, Emitter, Manager, PhysicalPosition, PhysicalRect, PhysicalSize,
+    Runtime, State, Window,
+};
+
+use crate::{config, core_process, error::MistError};
+
+pub const MAIN: &str = "main";
+
+const DEFAULT_TITLE_TEMPLATE: &str = "MIST";
+const MAX_TITLE_TEMPLATE_LEN: usize = 256;
+
+/// Whether the main window is kept above other windows.
+#[derive(Default)]
+pub struct AlwaysOnTop(Mutex<bool>);
+
+impl AlwaysOnTop {
+    pub fn get(&self) -> bool {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Outer position and inner size of a window, in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+/// A display, as reported by `get_available_monitors`. Position and size
+/// are in physical pixels.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonitorInfo {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub x: i32,
+    pub y: i32,
+    pub scale_factor: f64,
+}
+
+/// Show, un-minimise and focus the main window.
+pub fn show_main<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window(MAIN) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Hide the main window if it is in front, otherwise bring it there.
+pub fn toggle_main<R: Runtime>(app: &AppHandle<R>) {
+    let Some(window) = app.get_webview_window(MAIN) else {
+        return;
+    };
+    let in_front = window.is_visible().unwrap_or(false)
+        && !window.is_minimized().unwrap_or(false)
+        && window.is_focused().unwrap_or(false);
+    if in_front {
+        let _ = window.hide();
+    } else {
+        show_main(app);
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct SecondInstance {
+    args: Vec<String>,
+    cwd: String,
+}
+
+/// Single-instance callback: a second launch has handed over its
+/// arguments and exited. Focus the existing window and pass them on.
+pub fn on_second_instance<R: Runtime>(app: &AppHandle<R>, args: Vec<String>, cwd: String) {
+    tracing::info!(?args, "second instance launched");
+    show_main(app);
+    let _ = app.emit("second-instance", SecondInstance { args, cwd });
+}
+
+/// Current geometry of the main window.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub fn get_window_bounds<R: Runtime>(app: AppHandle<R>) -> Result<WindowBounds, MistError> {
+    Ok(bounds(&main_window(&app)?)?)
+}
+
+/// Move and resize the main window, clamped to the available monitors.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub fn set_window_bounds<R: Runtime>(
+    app: AppHandle<R>,
+    bounds: WindowBounds,
+) -> Result<(), MistError> {
+    Ok(apply_bounds(&main_window(&app)?, bounds)?)
+}
+
+/// Restore the geometry saved on the last close, if any.
+pub fn restore_bounds<R: Runtime>(app: &AppHandle<R>) -> Result<(), MistError> {
+    let config = config::load(app)?;
+    // Nothing to restore until the window has been closed once.
+    let Some(section) = config.get("window").filter(|s| s.get("x").is_some()) else {
+        return Ok(());
+    };
+    let Ok(saved) = section.clone().try_into::<WindowBounds>() else {
+        tracing::warn!("ignoring malformed [window] config");
+        return Ok(());
+    };
+    Ok(apply_bounds(&main_window(app)?, saved)?)
+}
+
+/// Displays currently connected, in the order `move_window_to_monitor`
+/// indexes them.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub fn get_available_monitors<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<MonitorInfo>, MistError> {
+    let monitors = app.available_monitors()?;
+    Ok(monitors
+        .iter()
+        .map(|monitor| MonitorInfo {
+            name: monitor.name().cloned().unwrap_or_default(),
+            width: monitor.size().width,
+            height: monitor.size().height,
+            x: monitor.position().x,
+            y: monitor.position().y,
+            scale_factor: monitor.scale_factor(),
+        })
+        .collect())
+}
+
+/// Centre the main window on the monitor at `index`.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub fn move_window_to_monitor<R: Runtime>(
+    app: AppHandle<R>,
+    index: usize,
+) -> Result<(), MistError> {
+    let window = main_window(&app)?;
+    let monitors = window.available_monitors()?;
+    let monitor = monitors
+        .get(index)
+        .ok_or_else(|| MistError::Validation("monitor index out of range".into()))?;
+    let size = window.outer_size()?;
+    let area = PhysicalRect {
+        position: *monitor.position(),
+        size: *monitor.size(),
+    };
+    Ok(window.set_position(centred(area, size))?)
+}
+
+/// Set the main window's title.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub fn set_window_title<R: Runtime>(app: AppHandle<R>, title: String) -> Result<(), MistError> {
+    Ok(main_window(&app)?.set_title(&title)?)
+}
+
+/// Save the template `resolve_title` fills in, e.g. `"MIST — {project}"`.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub fn set_title_template<R: Runtime>(
+    app: AppHandle<R>,
+    template: String,
+) -> Result<(), MistError> {
+    check_title_template(&template)?;
+    config::update(&app, |doc| {
+        let section = doc["window"].or_insert(toml_edit::table());
+        section["title_template"] = toml_edit::value(&template);
+    })
+}
+
+/// The saved title template with each `{key}` replaced by
+/// `variables[key]`. Fails if a placeholder has no value.
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub fn resolve_title<R: Runtime>(
+    app: AppHandle<R>,
+    variables: HashMap<String, String>,
+) -> Result<String, MistError> {
+    let config = config::load(&app)?;
+    let template = config
+        .get("window")
+        .and_then(|window| window.get("title_template"))
+        .and_then(|template| template.as_str())
+        .unwrap_or(DEFAULT_TITLE_TEMPLATE);
+    fill_template(template, &variables)
+}
+
+/// Title the main window from the saved template and `[project] name`.
+pub fn restore_title<R: Runtime>(app: &AppHandle<R>) -> Result<(), MistError> {
+    let config = config::load(app)?;
+    let mut variables = HashMap::new();
+    if let Some(name) = config
+        .get("project")
+        .and_then(|project| project.get("name"))
+        .and_then(|name| name.as_str())
+    {
+        variables.insert("project".to_string(), name.to_string());
+    }
+    let title = resolve_title(app.clone(), variables)?;
+    set_window_title(app.clone(), title)
+}
+
+/// Keep the main window above other windows, or stop doing so. Emits
+/// `window://always-on-top` with the new state.
+#[tauri::command]
+#[tracing::instrument(skip(app, state))]
+pub fn window_always_on_top<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AlwaysOnTop>,
+    enable: bool,
+) -> Result<(), MistError> {
+    apply_always_on_top(&app, &state, enable)?;
+    config::update(&app, |doc| {
+        let section = doc["window"].or_insert(toml_edit::table());
+        section["always_on_top"] = toml_edit::value(enable);
+    })
+}
+
+/// Re-pin the main window if `always_on_top` was saved as on.
+pub fn restore_always_on_top<R: Runtime>(app: &AppHandle<R>) -> Result<(), MistError> {
+    let config = config::load(app)?;
+    let enable = config
+        .get("window")
+        .and_then(|window| window.get("always_on_top"))
+        .and_then(|flag| flag.as_bool())
+        .unwrap_or(false);
+    if enable {
+        apply_always_on_top(app, &app.state(), true)?;
+    }
+    Ok(())
+}
+
+fn apply_always_on_top<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &AlwaysOnTop,
+    enable: bool,
+) -> Result<(), MistError> {
+    let mut current = state.0.lock()?;
+    main_window(app)?.set_always_on_top(enable)?;
+    *current = enable;
+    let _ = app.emit("window://always-on-top", enable);
+    Ok(())
+}
+
+/// Handle the close button on the main window: hide it to the tray if
+/// `minimize_to_tray` is set, otherwise let it close and stop the core.
+/// The setting is read from disk on every close so that edits made via
+/// `write_config` apply immediately.
+pub fn on_close_requested<R: Runtime>(window: &Window<R>, api: &CloseRequestApi) {
+    if let Err(e) = save_bounds(window) {
+        tracing::warn!("could not save window bounds: {e}");
+    }
+    if minimize_to_tray(window.app_handle()) {
+        api.prevent_close();
+        let _ = window.hide();
+    } else {
+        core_process::shutdown(window.app_handle());
+    }
+}
+
+fn minimize_to_tray<R: Runtime>(app: &AppHandle<R>) -> bool {
+    match config::load(app) {
+        Ok(config) => config
+            .get("window")
+            .and_then(|window| window.get("minimize_to_tray"))
+            .and_then(|flag| flag.as_bool())
+            .unwrap_or(false),
+        Err(e) => {
+            tracing::warn!("could not read config: {e}");
+            false
+        }
+    }
+}
+
+/// Persist the geometry of `window` to the `[window]` config table. While
+/// maximised only the flag is updated, so the restored size is the one
+/// the user last chose.
+fn save_bounds<R: Runtime>(window: &Window<R>) -> Result<(), MistError> {
+    let current = bounds(window)?;
+    config::update(window.app_handle(), |doc| {
+        let section = doc["window"].or_insert(toml_edit::table());
+        section["maximized"] = toml_edit::value(current.maximized);
+        if !current.maximized {
+            section["x"] = toml_edit::value(i64::from(current.x));
+            section["y"] = toml_edit::value(i64::from(current.y));
+            section["width"] = toml_edit::value(i64::from(current.width));
+            section["height"] = toml_edit::value(i64::from(current.height));
+        }
+    })
+}
+
+fn main_window<R: Runtime>(app: &AppHandle<R>) -> Result<Window<R>, MistError> {
+    app.get_webview_window(MAIN)
+        .map(|w| w.as_ref().window())
+        .ok_or_else(|| MistError::Process("main window not found".into()))
+}
+
+fn bounds<R: Runtime>(window: &Window<R>) -> tauri::Result<WindowBounds> {
+    let position = window.outer_position()?;
+    let size = window.inner_size()?;
+    Ok(WindowBounds {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: window.is_maximized()?,
+    })
+}
+
+fn apply_bounds<R: Runtime>(window: &Window<R>, bounds: WindowBounds) -> tauri::Result<()> {
+    let areas: Vec<_> = window
+        .available_monitors()?
+        .iter()
+        .map(|m| *m.work_area())
+        .collect();
+    let bounds = clamp_to_monitors(bounds, &areas);
+    window.set_size(PhysicalSize::new(bounds.width, bounds.height))?;
+    window.set_position(PhysicalPosition::new(bounds.x, bounds.y))?;
+    if bounds.maximized {
+        window.maximize()?;
+    }
+    Ok(())
+}
+
+/// Keep `bounds` on screen: pick the work area containing the window's
+/// centre (or the first one if none does) and pull the window inside it.
+fn clamp_to_monitors(bounds: WindowBounds, areas: &[PhysicalRect<i32, u32>]) -> WindowBounds {
+    let centre_x = i64::from(bounds.x) + i64::from(bounds.width) / 2;
+    let centre_y = i64::from(bounds.y) + i64::from(bounds.height) / 2;
+    let contains = |area: &&PhysicalRect<i32, u32>| {
+        let (left, top) = (i64::from(area.position.x), i64::from(area.position.y));
+        (left..left + i64::from(area.size.width)).contains(&centre_x)
+            && (top..top + i64::from(area.size.height)).contains(&centre_y)
+    };
+    let Some(area) = areas.iter().find(contains).or(areas.first()) else {
+        return bounds;
+    };
+
+    let width = bounds.width.min(area.size.width);
+    let height = bounds.height.min(area.size.height);
+    let max_x = area.position.x + (area.size.width - width) as i32;
+    let max_y = area.position.y + (area.size.height - height) as i32;
+    WindowBounds {
+        x: bounds.x.clamp(area.position.x, max_x),
+        y: bounds.y.clamp(area.position.y, max_y),
+        width,
+        height,
+        maximized: bounds.maximized,
+    }
+}
+
+fn check_title_template(template: &str) -> Result<(), MistError> {
+    if template.chars().count() > MAX_TITLE_TEMPLATE_LEN {
+        return Err(MistError::Validation(format!(
+            "title template is longer than {MAX_TITLE_TEMPLATE_LEN} characters"
+        )));
+    }
+    Ok(())
+}
+
+/// Replace each `{key}` in `template` with its value. A `{` with no
+/// closing `}` is kept as is.
+fn fill_template(template: &str, variables: &HashMap<String, String>) -> Result<String, MistError> {
+    check_title_template(template)?;
+    let mut title = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let key = &rest[start + 1..start + len];
+        let value = variables.get(key).ok_or_else(|| {
+            MistError::Validation(format!("no value for {{{key}}} in title template"))
+        })?;
+        title.push_str(&rest[..start]);
+        title.push_str(value);
+        rest = &rest[start + len + 1..];
+    }
+    title.push_str(rest);
+    Ok(title)
+}
+
+/// Top-left position that centres a window of `size` in `area`. A window
+/// larger than `area` is aligned to its top-left corner instead.
+fn centred(area: PhysicalRect<i32, u32>, size: PhysicalSize<u32>) -> PhysicalPosition<i32> {
+    let offset = |outer: u32, inner: u32| (outer.saturating_sub(inner) / 2) as i32;
+    PhysicalPosition::new(
+        area.position.x + offset(area.size.width, size.width),
+        area.position.y + offset(area.size.height, size.height),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tauri::WebviewWindowBuilder;
+
+    #[test]
+    fn move_rejects_out_of_range_index() {
+        let app = tauri::test::mock_app();
+        WebviewWindowBuilder::new(&app, MAIN, Default::default())
+            .build()
+            .unwrap();
+
+        // The mock runtime reports no monitors, so every index is invalid.
+        let err = move_window_to_monitor(app.handle().clone(), 0).unwrap_err();
+        assert_eq!(
+            err,
+            MistError::Validation("monitor index out of range".into())
+        );
+    }
+
+    #[test]
+    fn fills_title_placeholders() {
+        let variables = HashMap::from([
+            ("project".to_string(), "survey".to_string()),
+            ("branch".to_string(), "main".to_string()),
+        ]);
+        let title = fill_template("MIST — {project} ({branch}) {", &variables).unwrap();
+        assert_eq!(title, "MIST — survey (main) {");
+
+        let err = fill_template("MIST — {user}", &variables).unwrap_err();
+        assert!(matches!(&err, MistError::Validation(m) if m.contains("{user}")));
+    }
+
+    #[test]
+    fn rejects_long_title_template() {
+        assert!(check_title_template(&"é".repeat(MAX_TITLE_TEMPLATE_LEN)).is_ok());
+        assert!(check_title_template(&"x".repeat(MAX_TITLE_TEMPLATE_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn centres_window_on_monitor() {
+        let area = PhysicalRect {
+            position: PhysicalPosition::new(1920, 0),
+            size: PhysicalSize::new(2560, 1440),
+        };
+        let position = centred(area, PhysicalSize::new(1280, 800));
+        assert_eq!(position, PhysicalPosition::new(1920 + 640, 320));
+
+        let position = centred(area, PhysicalSize::new(4000, 2000));
+        assert_eq!(position, PhysicalPosition::new(1920, 0));
+    }
+
+    fn area(x: i32, y: i32, width: u32, height: u32) -> PhysicalRect<i32, u32> {
+        PhysicalRect {
+            position: PhysicalPosition::new(x, y),
+            size: PhysicalSize::new(width, height),
+        }
+    }
+
+    fn bounds(x: i32, y: i32, width: u32, height: u32) -> WindowBounds {
+        WindowBounds {
+            x,
+            y,
+            width,
+            height,
+            maximized: false,
+        }
+    }
+
+    /// A laptop panel with a larger monitor to its right.
+    fn dual() -> [PhysicalRect<i32, u32>; 2] {
+        [area(0, 0, 1920, 1040), area(1920, 0, 2560, 1400)]
+    }
+
+    #[test]
+    fn clamps_to_the_monitor_holding_the_centre() {
+        // Fully on the second monitor: left alone.
+        let inside = bounds(2000, 100, 800, 600);
+        assert_eq!(clamp_to_monitors(inside, &dual()), inside);
+
+        // Centre on the second monitor but hanging off its right edge.
+        let overhang = bounds(4000, 100, 800, 600);
+        assert_eq!(
+            clamp_to_monitors(overhang, &dual()),
+            bounds(1920 + 2560 - 800, 100, 800, 600)
+        );
+    }
+
+    #[test]
+    fn shrinks_a_window_larger_than_the_work_area() {
+        let huge = WindowBounds {
+            maximized: true,
+            ..bounds(100, 100, 3000, 2000)
+        };
+        assert_eq!(
+            clamp_to_monitors(huge, &[area(0, 0, 1920, 1040)]),
+            WindowBounds {
+                maximized: true,
+                ..bounds(0, 0, 1920, 1040)
+            }
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_first_monitor_when_disconnected() {
+        // Saved while a monitor to the right was attached.
+        let orphaned = bounds(5000, 200, 800, 600);
+        assert_eq!(
+            clamp_to_monitors(orphaned, &dual()[..1]),
+            bounds(1920 - 800, 200, 800, 600)
+        );
+    }
+
+    #[test]
+    fn keeps_bounds_without_monitors() {
+        let saved = bounds(-500, 9000, 800, 600);
+        assert_eq!(clamp_to_monitors(saved, &[]), saved);
+    }
+}
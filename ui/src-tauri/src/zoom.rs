@@ -0,0 +1,103 @@
+// WebView zoom, for high-DPI displays and users who need larger text.
+//
+// Tauri can set the zoom factor but not read it back, so the current
+// level is tracked here. It is saved as `zoom_level` in the `[window]`
+// config table, restored at startup, and every change is announced on
+// `zoom://changed` so the UI can reflow.
+
+use std::ops::RangeInclusive;
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::{config, error::MistError, window};
+
+const ZOOM_RANGE: RangeInclusive<f64> = 0.5..=3.0;
+
+/// Zoom factor of the main window; 1.0 is 100%.
+pub struct ZoomLevel(Mutex<f64>);
+
+impl Default for ZoomLevel {
+    fn default() -> Self {
+        Self(Mutex::new(1.0))
+    }
+}
+
+/// Restore the zoom level saved by a previous session.
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), MistError> {
+    let config = config::load(app)?;
+    let Some(factor) = config
+        .get("window")
+        .and_then(|window| window.get("zoom_level"))
+        .and_then(|factor| factor.as_float())
+    else {
+        return Ok(());
+    };
+    check_factor(factor)?;
+    apply(app, &app.state(), factor)
+}
+
+/// Zoom the main window by `factor`, between 0.5 and 3.0.
+#[tauri::command]
+#[tracing::instrument(skip(app, zoom))]
+pub fn set_zoom_level<R: Runtime>(
+    app: AppHandle<R>,
+    zoom: State<'_, ZoomLevel>,
+    factor: f64,
+) -> Result<(), MistError> {
+    check_factor(factor)?;
+    apply(&app, &zoom, factor)?;
+    config::update(&app, |doc| {
+        let section = doc["window"].or_insert(toml_edit::table());
+        section["zoom_level"] = toml_edit::value(factor);
+    })
+}
+
+/// The main window's current zoom factor.
+#[tauri::command]
+#[tracing::instrument(skip(zoom))]
+pub fn get_zoom_level(zoom: State<'_, ZoomLevel>) -> f64 {
+    *zoom.0.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn check_factor(factor: f64) -> Result<(), MistError> {
+    if !ZOOM_RANGE.contains(&factor) {
+        return Err(MistError::Validation(format!(
+            "factor out of range [{:.1}, {:.1}]",
+            ZOOM_RANGE.start(),
+            ZOOM_RANGE.end()
+        )));
+    }
+    Ok(())
+}
+
+fn apply<R: Runtime>(app: &AppHandle<R>, zoom: &ZoomLevel, factor: f64) -> Result<(), MistError> {
+    let mut current = zoom.0.lock()?;
+    app.get_webview_window(window::MAIN)
+        .ok_or_else(|| MistError::Process("main window not found".into()))?
+        .set_zoom(factor)?;
+    if *current != factor {
+        *current = factor;
+        let _ = app.emit("zoom://changed", factor);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_factor_out_of_range() {
+        for factor in [0.5, 1.0, 2.25, 3.0] {
+            assert!(check_factor(factor).is_ok(), "{factor}");
+        }
+        for factor in [0.49, 3.01, -1.0, f64::NAN, f64::INFINITY] {
+            assert_eq!(
+                check_factor(factor).unwrap_err(),
+                MistError::Validation("factor out of range [0.5, 3.0]".into()),
+                "{factor}"
+            );
+        }
+    }
+}
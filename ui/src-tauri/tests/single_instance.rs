@@ -0,0 +1,79 @@
+//! Launches the real app twice and checks that the second launch hands
+//! off to the first instead of opening its own window.
+//!
+//! Needs a desktop session and a free app identifier, so it is ignored by
+//! default: `cargo test --test single_instance -- --ignored`.
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const READY: &str = "main window ready";
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+fn launch() -> Child {
+    Command::new(env!("CARGO_BIN_EXE_mist-ui"))
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to launch mist-ui")
+}
+
+/// Forward each stderr line of `child` to the returned channel.
+fn stderr_lines(child: &mut Child) -> mpsc::Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    let stderr = child.stderr.take().unwrap();
+    thread::spawn(move || {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+fn wait_for_ready(lines: &mpsc::Receiver<String>) -> bool {
+    let deadline = Instant::now() + TIMEOUT;
+    while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+        match lines.recv_timeout(left) {
+            Ok(line) if line.contains(READY) => return true,
+            Ok(_) => continue,
+            Err(_) => return false,
+        }
+    }
+    false
+}
+
+#[test]
+#[ignore = "launches the GUI app"]
+fn second_launch_defers_to_first() {
+    let mut first = launch();
+    let first_lines = stderr_lines(&mut first);
+    assert!(
+        wait_for_ready(&first_lines),
+        "first instance never became ready"
+    );
+
+    let mut second = launch();
+    let second_lines = stderr_lines(&mut second);
+    let deadline = Instant::now() + TIMEOUT;
+    let status = loop {
+        if let Some(status) = second.try_wait().unwrap() {
+            break status;
+        }
+        assert!(Instant::now() < deadline, "second instance did not exit");
+        thread::sleep(Duration::from_millis(100));
+    };
+    assert_eq!(status.code(), Some(0));
+    assert!(
+        !second_lines.try_iter().any(|line| line.contains(READY)),
+        "second instance reached the ready state"
+    );
+    assert!(first.try_wait().unwrap().is_none(), "first instance exited");
+
+    first.kill().unwrap();
+    first.wait().unwrap();
+}
@@ -14,9 +14,10 @@ import {
 import { store, type ChatEntry } from "./store";
 
 const UI_ID = "ui";
-const WS_URL = "ws://127.0.0.1:8765";
+const DEFAULT_WS_URL = "ws://127.0.0.1:8765";
 const RECONNECT_DELAY = 3000;
 
+let wsUrl = DEFAULT_WS_URL;
 let ws: WebSocket | null = null;
 let reconnectTimer: ReturnType<typeof setTimeout> | null = null;
 
@@ -34,7 +35,7 @@ export function onResponse(
 export function connect(): void {
   if (ws && ws.readyState <= WebSocket.OPEN) return;
 
-  ws = new WebSocket(WS_URL);
+  ws = new WebSocket(wsUrl);
 
   ws.addEventListener("open", () => {
     store.setConnected(true);
@@ -62,6 +63,16 @@ export function connect(): void {
   });
 }
 
+/** Point the connection at a core listening on `port` and reconnect. */
+export function setCorePort(port: number): void {
+  wsUrl = `ws://127.0.0.1:${port}`;
+  if (ws) {
+    ws.close();
+  } else {
+    connect();
+  }
+}
+
 function scheduleReconnect(): void {
   if (reconnectTimer) return;
   reconnectTimer = setTimeout(() => {
@@ -1,6 +1,7 @@
 /** MIST UI entry point. */
 
-import { connect } from "./app";
+import { listen } from "@tauri-apps/api/event";
+import { connect, setCorePort } from "./app";
 import { mountAgentTabs } from "./components/AgentTabs";
 import { mountBrowserPanel } from "./components/BrowserPanel";
 import { mountBrokerDashboard } from "./components/BrokerDashboard";
@@ -25,5 +26,6 @@ mountBrokerDashboard(panel);
 mountStatusBar(status);
 mountErrorPopup(errorPopup);
 
-// Connect to broker WebSocket
+// Connect to broker WebSocket, following the managed core if it moves
 connect();
+listen<{ port: number }>("core://port", (event) => setCorePort(event.payload.port));